@@ -11,6 +11,7 @@ import {
 } from "@solana/web3.js";
 import BN from "bn.js";
 import fs from "fs";
+import { createHash } from "crypto";
 import os from "os";
 import chalk from "chalk";
 import { Dashboard } from "./dashboard";
@@ -55,6 +56,10 @@ class NodeWallet implements anchor.Wallet {
   }
 }
 
+// Demo deliverables are placeholders, so hash the URI itself as the content hash
+const resultHash = (content: string): number[] =>
+  Array.from(createHash("sha256").update(content).digest());
+
 function loadMainWallet(): Keypair {
   const walletPath = `${os.homedir()}/.config/solana/id.json`;
   const secretKey = JSON.parse(fs.readFileSync(walletPath, "utf-8"));
@@ -71,21 +76,26 @@ const getAgentProfilePDA = (owner: PublicKey): [PublicKey, number] =>
     PROGRAM_ID
   );
 
-const getJobPDA = (
-  client: PublicKey,
-  agentProfile: PublicKey,
-  ts: BN
-): [PublicKey, number] =>
+const getJobCounterPDA = (creator: PublicKey): [PublicKey, number] =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("job_counter"), creator.toBuffer()],
+    PROGRAM_ID
+  );
+
+const getJobPDA = (creator: PublicKey, nonce: BN): [PublicKey, number] =>
   PublicKey.findProgramAddressSync(
-    [
-      Buffer.from("job"),
-      client.toBuffer(),
-      agentProfile.toBuffer(),
-      ts.toArrayLike(Buffer, "le", 8),
-    ],
+    [Buffer.from("job"), creator.toBuffer(), nonce.toArrayLike(Buffer, "le", 8)],
     PROGRAM_ID
   );
 
+/** PDA of the next job `creator` opens, read from its on-chain JobCounter */
+async function nextJobPDA(program: anchor.Program, creator: PublicKey): Promise<PublicKey> {
+  const counter: any = await (program.account as any).jobCounter.fetchNullable(
+    getJobCounterPDA(creator)[0]
+  );
+  return getJobPDA(creator, counter ? counter.nextNonce : new BN(0))[0];
+}
+
 const getRatingPDA = (job: PublicKey): [PublicKey, number] =>
   PublicKey.findProgramAddressSync(
     [Buffer.from("rating"), job.toBuffer()],
@@ -98,9 +108,6 @@ const getRatingPDA = (job: PublicKey): [PublicKey, number] =>
 
 const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
 
-let tsCounter = Math.floor(Date.now() / 1000);
-const nextTs = () => new BN(tsCounter++);
-
 function stepLabel(step: number, total: number, msg: string): void {
   console.log(
     chalk.bgCyan.black.bold(` DEMO `) +
@@ -355,18 +362,29 @@ async function main() {
     // ── Step 3: Client invokes Aurora ──
     dashboard.addAnnotation('Step 3/13: Client invokes Aurora -- "Review and audit this smart contract"');
     await sleep(2000);
-    const job1Ts = nextTs();
-    const [job1PDA] = getJobPDA(mainWallet.publicKey, auroraProfilePDA, job1Ts);
+    const job1PDA = await nextJobPDA(mainProgram, mainWallet.publicKey);
     await mainProgram.methods
       .invokeAgent(
         "Review and audit this smart contract",
         JOB1_PAYMENT,
-        new BN(3600), // 1 hour auto-release
-        job1Ts
+        {
+          autoReleaseSeconds: new BN(3600), // 1 hour auto-release
+          stakeRequirement: null, // no minimum stake
+          stream: null, // not streamed
+          clientEncryptionKey: null, // plaintext delivery
+          spec: null, // title only, no off-chain spec
+          delegationPolicy: null, // unrestricted delegation
+          requiredCapabilities: 0, // no specific capability: default price
+          priceTier: 0, // base size tier
+          maxHeartbeatAge: null, // no liveness requirement
+        }
       )
       .accountsPartial({
         client: mainWallet.publicKey,
         agentProfile: auroraProfilePDA,
+        job: job1PDA,
+        jobExtension: null, // no stream, encryption or delegation policy
+        agentStake: null,
       })
       .signers([mainWallet])
       .rpc();
@@ -376,10 +394,13 @@ async function main() {
     dashboard.addAnnotation("Step 4/13: Aurora completes the job...");
     await sleep(2000);
     await mainProgram.methods
-      .updateJob("ipfs://QmAuditResult_v1_full_review_clean")
+      .updateJob("ipfs://QmAuditResult_v1_full_review_clean",
+        resultHash("ipfs://QmAuditResult_v1_full_review_clean"), null)
       .accountsPartial({
         agent: mainWallet.publicKey,
         job: job1PDA,
+        agentProfile: auroraProfilePDA,
+        jobExtension: null,
       })
       .signers([mainWallet])
       .rpc();
@@ -389,13 +410,14 @@ async function main() {
     dashboard.addAnnotation("Step 5/13: Client releases payment to Aurora...");
     await sleep(2000);
     await mainProgram.methods
-      .releasePayment()
+      .releasePayment(null)
       .accountsPartial({
         client: mainWallet.publicKey,
         agent: mainWallet.publicKey,
         agentProfile: auroraProfilePDA,
         job: job1PDA,
         parentJob: null,
+        team: null,
       })
       .signers([mainWallet])
       .rpc();
@@ -418,18 +440,29 @@ async function main() {
     // ── Step 7: New job with delegation ──
     dashboard.addAnnotation('Step 7/13: New job -- "Full security audit with specialist review" (0.08 SOL)');
     await sleep(3000);
-    const job2Ts = nextTs();
-    const [job2PDA] = getJobPDA(mainWallet.publicKey, auroraProfilePDA, job2Ts);
+    const job2PDA = await nextJobPDA(mainProgram, mainWallet.publicKey);
     await mainProgram.methods
       .invokeAgent(
         "Full security audit with specialist review",
         JOB2_PAYMENT,
-        null, // no auto-release
-        job2Ts
+        {
+          autoReleaseSeconds: null, // no auto-release
+          stakeRequirement: null, // no minimum stake
+          stream: null, // not streamed
+          clientEncryptionKey: null, // plaintext delivery
+          spec: null, // title only, no off-chain spec
+          delegationPolicy: null, // unrestricted delegation
+          requiredCapabilities: 0, // no specific capability: default price
+          priceTier: 0, // base size tier
+          maxHeartbeatAge: null, // no liveness requirement
+        }
       )
       .accountsPartial({
         client: mainWallet.publicKey,
         agentProfile: auroraProfilePDA,
+        job: job2PDA,
+        jobExtension: null, // no stream, encryption or delegation policy
+        agentStake: null,
       })
       .signers([mainWallet])
       .rpc();
@@ -438,22 +471,21 @@ async function main() {
     // ── Step 8: Aurora delegates to CodeAuditor ──
     dashboard.addAnnotation("Step 8/13: Aurora delegates subtask to CodeAuditor (0.03 SOL)...");
     await sleep(3000);
-    const childTs = nextTs();
-    const [childJobPDA] = getJobPDA(
-      mainWallet.publicKey,
-      auditorProfilePDA,
-      childTs
-    );
+    const childJobPDA = await nextJobPDA(mainProgram, mainWallet.publicKey);
     await mainProgram.methods
       .delegateTask(
         "Perform deep security analysis of reentrancy vectors",
         DELEGATION_AMT,
-        childTs
+        null // title only, no off-chain spec
       )
       .accountsPartial({
         delegatingAgent: mainWallet.publicKey,
         parentJob: job2PDA,
+        agentProfile: auroraProfilePDA,
+        team: null, // solo parent job
+        parentExtension: null,
         subAgentProfile: auditorProfilePDA,
+        childJob: childJobPDA,
       })
       .signers([mainWallet])
       .rpc();
@@ -463,10 +495,13 @@ async function main() {
     dashboard.addAnnotation("Step 9/13: CodeAuditor completes subtask...");
     await sleep(2000);
     await auditorProgram.methods
-      .updateJob("ipfs://QmSecurityAudit_reentrancy_clean_report")
+      .updateJob("ipfs://QmSecurityAudit_reentrancy_clean_report",
+        resultHash("ipfs://QmSecurityAudit_reentrancy_clean_report"), null)
       .accountsPartial({
         agent: auditorKeypair.publicKey,
         job: childJobPDA,
+        agentProfile: auditorProfilePDA,
+        jobExtension: null,
       })
       .signers([auditorKeypair])
       .rpc();
@@ -476,13 +511,14 @@ async function main() {
     dashboard.addAnnotation("Step 10/13: Aurora releases payment to CodeAuditor...");
     await sleep(2000);
     await mainProgram.methods
-      .releasePayment()
+      .releasePayment(null)
       .accountsPartial({
         client: mainWallet.publicKey,
         agent: auditorKeypair.publicKey,
         agentProfile: auditorProfilePDA,
         job: childJobPDA,
         parentJob: job2PDA,
+        team: null,
       })
       .signers([mainWallet])
       .rpc();
@@ -492,10 +528,13 @@ async function main() {
     dashboard.addAnnotation("Step 11/13: Aurora completes parent job...");
     await sleep(2000);
     await mainProgram.methods
-      .updateJob("ipfs://QmFullAudit_combined_report_final")
+      .updateJob("ipfs://QmFullAudit_combined_report_final",
+        resultHash("ipfs://QmFullAudit_combined_report_final"), null)
       .accountsPartial({
         agent: mainWallet.publicKey,
         job: job2PDA,
+        agentProfile: auroraProfilePDA,
+        jobExtension: null,
       })
       .signers([mainWallet])
       .rpc();
@@ -505,13 +544,14 @@ async function main() {
     dashboard.addAnnotation("Step 12/13: Client releases remaining payment to Aurora (0.05 SOL)...");
     await sleep(2000);
     await mainProgram.methods
-      .releasePayment()
+      .releasePayment(null)
       .accountsPartial({
         client: mainWallet.publicKey,
         agent: mainWallet.publicKey,
         agentProfile: auroraProfilePDA,
         job: job2PDA,
         parentJob: null,
+        team: null,
       })
       .signers([mainWallet])
       .rpc();
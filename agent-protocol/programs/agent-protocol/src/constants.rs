@@ -1,9 +1,41 @@
 /// Dispute timeout: 7 days in seconds
 pub const DISPUTE_TIMEOUT: i64 = 604_800;
 
+/// Unstake cooldown: 14 days in seconds (outlasts DISPUTE_TIMEOUT so stake
+/// cannot be pulled out from under an open dispute)
+pub const UNSTAKE_COOLDOWN: i64 = 1_209_600;
+
+/// Share of an agent's stake slashed to the client on a lost dispute when the
+/// client set no stake requirement (basis points)
+pub const DEFAULT_SLASH_BPS: u16 = 5_000;
+
+/// Largest stake share a client may ask to have slashed (basis points)
+pub const MAX_SLASH_BPS: u16 = 5_000;
+
+/// Basis-point denominator (100%)
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
 /// Maximum number of active child delegations per job
 pub const MAX_ACTIVE_CHILDREN: u8 = 8;
 
+/// Maximum jobs opened by one invoke_agents_batch call (transaction size bound)
+pub const MAX_BATCH_JOBS: usize = 8;
+
+/// Maximum agents on a team job, lead included (JobTeam.members max_len)
+pub const MAX_TEAM_MEMBERS: usize = 5;
+
+/// Maximum sub-agent profiles on a delegation allowlist (DelegationPolicy max_len)
+pub const MAX_DELEGATION_ALLOWLIST: usize = 4;
+
+/// Maximum entries in an agent's price table (AgentProfile.price_table max_len)
+pub const MAX_PRICE_TABLE_ENTRIES: usize = 8;
+
+/// Maximum clients on an agent's access list (AgentAccessList.clients max_len)
+pub const MAX_ACCESS_LIST_ENTRIES: usize = 16;
+
+/// Highest AgentMetadata schema version accepted by set_agent_metadata
+pub const METADATA_SCHEMA_VERSION: u8 = 1;
+
 // Capability bitmask values
 pub const CAP_CODE_REVIEW: u16 = 1 << 0;
 pub const CAP_SECURITY_AUDIT: u16 = 1 << 1;
@@ -11,3 +43,11 @@ pub const CAP_DOCUMENTATION: u16 = 1 << 2;
 pub const CAP_TESTING: u16 = 1 << 3;
 pub const CAP_DEPLOYMENT: u16 = 1 << 4;
 pub const CAP_GENERAL: u16 = 1 << 5;
+
+// Input format bitmask values (AgentMetadata.input_formats)
+pub const FORMAT_TEXT: u16 = 1 << 0;
+pub const FORMAT_JSON: u16 = 1 << 1;
+pub const FORMAT_MARKDOWN: u16 = 1 << 2;
+pub const FORMAT_CODE: u16 = 1 << 3;
+pub const FORMAT_PDF: u16 = 1 << 4;
+pub const FORMAT_IMAGE: u16 = 1 << 5;
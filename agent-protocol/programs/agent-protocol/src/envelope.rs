@@ -0,0 +1,81 @@
+//! Encrypted result delivery.
+//!
+//! The client publishes an X25519 public key when invoking an agent, and the agent
+//! publishes one on its `AgentProfile`. On delivery the agent encrypts the result
+//! under a random content key, then wraps that key for the client:
+//!
+//! 1. `shared = X25519(agent_secret, client_public)` (client: `X25519(client_secret, agent_public)`)
+//! 2. `wrap_key = derive_wrap_key(shared, job, client_public, agent_public)`
+//! 3. `wrapped_key = ChaCha20-Poly1305(wrap_key, nonce, content_key, associated_data(job, result_hash))`
+//!
+//! The program cannot run X25519 itself, so it only checks the envelope's shape and
+//! that it names the agent's registered key; the derivation helpers here are what
+//! off-chain Rust clients use to produce and open envelopes byte-for-byte.
+
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+use crate::error::AgentProtocolError;
+
+/// Current envelope format version
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// Domain separator for the wrap-key derivation
+pub const WRAP_KEY_DOMAIN: &[u8] = b"agent-protocol/result-key/v1";
+
+/// ChaCha20-Poly1305 nonce length
+pub const NONCE_LEN: usize = 12;
+
+/// 32-byte content key followed by the 16-byte Poly1305 tag
+pub const WRAPPED_KEY_LEN: usize = 48;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct ResultEnvelope {
+    pub version: u8,
+    /// Agent X25519 public key used for the exchange
+    pub agent_key: [u8; 32],
+    pub nonce: [u8; NONCE_LEN],
+    pub wrapped_key: [u8; WRAPPED_KEY_LEN],
+}
+
+impl ResultEnvelope {
+    /// Format checks the program can do without the shared secret
+    pub fn validate(&self, registered_agent_key: &[u8; 32]) -> Result<()> {
+        require!(self.version == ENVELOPE_VERSION, AgentProtocolError::InvalidEnvelope);
+        require!(
+            self.agent_key == *registered_agent_key,
+            AgentProtocolError::InvalidEnvelope
+        );
+        require!(self.nonce != [0u8; NONCE_LEN], AgentProtocolError::InvalidEnvelope);
+        require!(
+            self.wrapped_key != [0u8; WRAPPED_KEY_LEN],
+            AgentProtocolError::InvalidEnvelope
+        );
+        Ok(())
+    }
+}
+
+/// Key-encryption key both sides derive from the X25519 shared secret.
+/// Binding the job and both public keys makes every job's wrap key unique.
+pub fn derive_wrap_key(
+    shared_secret: &[u8; 32],
+    job: &Pubkey,
+    client_key: &[u8; 32],
+    agent_key: &[u8; 32],
+) -> [u8; 32] {
+    hashv(&[
+        WRAP_KEY_DOMAIN,
+        shared_secret,
+        job.as_ref(),
+        client_key,
+        agent_key,
+    ])
+    .to_bytes()
+}
+
+/// AEAD associated data: ties the wrapped key to one job and one deliverable
+pub fn associated_data(job: &Pubkey, result_hash: &[u8; 32]) -> [u8; 64] {
+    let mut ad = [0u8; 64];
+    ad[..32].copy_from_slice(job.as_ref());
+    ad[32..].copy_from_slice(result_hash);
+    ad
+}
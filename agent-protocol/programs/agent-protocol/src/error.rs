@@ -38,4 +38,126 @@ pub enum AgentProtocolError {
     Overflow,
     #[msg("Too many active delegations (max 8)")]
     TooManyDelegations,
+    #[msg("Stake amount must be greater than zero")]
+    InvalidStakeAmount,
+    #[msg("Agent stake is below the required minimum")]
+    InsufficientStake,
+    #[msg("Unstake cooldown has not elapsed")]
+    UnstakeCooldownActive,
+    #[msg("No unstaked lamports available to withdraw")]
+    NothingToWithdraw,
+    #[msg("Bounty claim deadline has passed")]
+    BountyExpired,
+    #[msg("Agent lacks the required capabilities")]
+    CapabilityMismatch,
+    #[msg("Agent rating is below the required minimum")]
+    RatingTooLow,
+    #[msg("Bidding window has closed")]
+    BiddingClosed,
+    #[msg("Bidding or reveal window is still open")]
+    BiddingStillOpen,
+    #[msg("Bid does not match the request's bidding mode")]
+    InvalidBid,
+    #[msg("Bid exceeds the request budget")]
+    BidExceedsBudget,
+    #[msg("Bid has not been revealed")]
+    BidNotRevealed,
+    #[msg("Revealed bid does not match its commitment")]
+    BidCommitmentMismatch,
+    #[msg("Bid does not belong to this job request")]
+    JobRequestMismatch,
+    #[msg("URI too long (max 128 chars)")]
+    UriTooLong,
+    #[msg("Duration must be greater than zero")]
+    InvalidDuration,
+    #[msg("Subscription period has not elapsed")]
+    PeriodNotElapsed,
+    #[msg("Subscription is paused")]
+    SubscriptionPaused,
+    #[msg("Subscription is not paused")]
+    SubscriptionNotPaused,
+    #[msg("All subscription periods have been claimed")]
+    SubscriptionComplete,
+    #[msg("Stream must end after it starts and in the future")]
+    InvalidStreamWindow,
+    #[msg("Job is not a streaming job")]
+    NotStreamingJob,
+    #[msg("Operation not supported on a started streaming job")]
+    StreamingJob,
+    #[msg("Stream is frozen by a dispute")]
+    StreamFrozen,
+    #[msg("Result hash is required")]
+    EmptyResultHash,
+    #[msg("Result hash does not match the committed hash")]
+    ResultHashMismatch,
+    #[msg("Result already committed")]
+    ResultAlreadyCommitted,
+    #[msg("Encryption key must be non-zero")]
+    InvalidEncryptionKey,
+    #[msg("Agent has not published an encryption key")]
+    EncryptionKeyMissing,
+    #[msg("Client requested encrypted delivery; envelope required")]
+    EnvelopeRequired,
+    #[msg("Result envelope is malformed or not for this job")]
+    InvalidEnvelope,
+    #[msg("Title too long (max 64 chars)")]
+    TitleTooLong,
+    #[msg("Spec needs a URI and a non-zero hash")]
+    InvalidSpec,
+    #[msg("Team must have 2-5 members with one profile per share")]
+    InvalidTeamSize,
+    #[msg("Team shares must be non-zero and sum to 10000 bps")]
+    InvalidTeamShares,
+    #[msg("Agent appears twice on the team")]
+    DuplicateTeamMember,
+    #[msg("Team accounts do not match the job's roster")]
+    TeamMismatch,
+    #[msg("Signer is not a team member")]
+    NotTeamMember,
+    #[msg("Team member already signed off")]
+    AlreadySignedOff,
+    #[msg("Batch needs 1-8 entries, each with an agent profile and its job PDA")]
+    InvalidBatch,
+    #[msg("No partial release has been proposed for this job")]
+    NoPartialReleaseProposal,
+    #[msg("Accepted amount does not match the client's proposal")]
+    PartialReleaseMismatch,
+    #[msg("Delegation policy needs max_share_bps <= 10000 and at most 4 allowed sub-agents")]
+    InvalidDelegationPolicy,
+    #[msg("Client does not allow delegation on this job")]
+    DelegationNotAllowed,
+    #[msg("Delegation exceeds the client's max share of the escrow")]
+    DelegationShareExceeded,
+    #[msg("Sub-agent is not on the client's allowlist")]
+    SubAgentNotAllowed,
+    #[msg("Sub-agent rating is below the client's minimum")]
+    SubAgentRatingTooLow,
+    #[msg("Agent is at its concurrent job limit")]
+    AgentAtCapacity,
+    #[msg("Job is already assigned to this agent")]
+    ReassignToSameAgent,
+    #[msg("Price table entries need one offered capability bit, a non-zero price and a unique tier (max 8)")]
+    InvalidPriceTable,
+    #[msg("Payout wallet cannot be the default address")]
+    InvalidPayoutWallet,
+    #[msg("No ownership transfer is pending for this agent")]
+    NoPendingTransfer,
+    #[msg("Agent metadata needs an endpoint, a hash for any metadata URI and a supported schema version")]
+    InvalidAgentMetadata,
+    #[msg("Agent has not sent a heartbeat within the required age")]
+    AgentStale,
+    #[msg("Agent's access list does not permit this client")]
+    ClientNotPermitted,
+    #[msg("Access list too long (max 16 clients)")]
+    AccessListTooLong,
+    #[msg("Slash share must not exceed 5000 bps")]
+    InvalidSlashBps,
+    #[msg("Access list account does not belong to the agent")]
+    AccessListMismatch,
+    #[msg("Stake cannot be withdrawn while client disputes are open")]
+    DisputesOpen,
+    #[msg("Agent still has jobs in flight")]
+    AgentHasActiveJobs,
+    #[msg("Job extension account missing or not expected")]
+    ExtensionMismatch,
 }
@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use crate::envelope::ResultEnvelope;
+use crate::state::{AccessMode, CapabilityPrice};
 
 #[event]
 pub struct AgentRegistered {
@@ -22,6 +24,12 @@ pub struct JobCompleted {
     pub job: Pubkey,
     pub agent: Pubkey,
     pub result_uri: String,
+    pub result_hash: [u8; 32],
+    /// Wrapped content key when the client requested encrypted delivery
+    pub envelope: Option<ResultEnvelope>,
+    /// Running hash of the job's finalized child results (zero without children)
+    pub child_results_hash: [u8; 32],
+    pub children_completed: u32,
 }
 
 #[event]
@@ -45,7 +53,13 @@ pub struct PaymentReleased {
     pub job: Pubkey,
     pub agent: Pubkey,
     pub amount: u64,
+    /// Client tip paid on top of `amount`
+    pub bonus: u64,
     pub auto_released: bool,
+    /// Parent jobs only: escrow the delegating agent kept after sub-agents were
+    /// paid, i.e. original_escrow - delegated_total - any partial-release
+    /// refund - other team members' shares
+    pub margin_lamports: Option<u64>,
 }
 
 #[event]
@@ -67,3 +81,290 @@ pub struct AgentRated {
     pub score: u8,
     pub new_avg_x100: u64,
 }
+
+#[event]
+pub struct AgentStaked {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct UnstakeRequested {
+    pub agent: Pubkey,
+    pub amount: u64,
+    pub available_at: i64,
+}
+
+#[event]
+pub struct StakeWithdrawn {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct StakeSlashed {
+    pub agent: Pubkey,
+    pub job: Pubkey,
+    pub client: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BountyPosted {
+    pub job: Pubkey,
+    pub client: Pubkey,
+    pub escrow_lamports: u64,
+    pub required_capabilities: u16,
+    pub min_rating_x100: u16,
+    pub claim_deadline: Option<i64>,
+}
+
+#[event]
+pub struct BountyClaimed {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub agent_profile: Pubkey,
+}
+
+#[event]
+pub struct JobRequestCreated {
+    pub job_request: Pubkey,
+    pub client: Pubkey,
+    pub max_budget_lamports: u64,
+    pub sealed: bool,
+    pub bid_deadline: i64,
+    pub reveal_deadline: Option<i64>,
+}
+
+#[event]
+pub struct BidSubmitted {
+    pub job_request: Pubkey,
+    pub bid: Pubkey,
+    pub agent: Pubkey,
+    /// Zero for sealed bids until revealed
+    pub price_lamports: u64,
+    pub sealed: bool,
+}
+
+#[event]
+pub struct BidRevealed {
+    pub job_request: Pubkey,
+    pub bid: Pubkey,
+    pub agent: Pubkey,
+    pub price_lamports: u64,
+    pub eta_seconds: i64,
+}
+
+#[event]
+pub struct BidAccepted {
+    pub job_request: Pubkey,
+    pub bid: Pubkey,
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub price_lamports: u64,
+}
+
+#[event]
+pub struct BidClosed {
+    pub job_request: Pubkey,
+    pub bid: Pubkey,
+    pub agent: Pubkey,
+}
+
+#[event]
+pub struct JobRequestCancelled {
+    pub job_request: Pubkey,
+    pub client: Pubkey,
+}
+
+#[event]
+pub struct SubscriptionCreated {
+    pub subscription: Pubkey,
+    pub client: Pubkey,
+    pub agent: Pubkey,
+    pub amount_per_period: u64,
+    pub period_seconds: i64,
+    pub total_periods: u32,
+}
+
+#[event]
+pub struct SubscriptionPeriodClaimed {
+    pub subscription: Pubkey,
+    pub agent: Pubkey,
+    pub amount: u64,
+    /// 1-based index of the period just paid
+    pub period: u32,
+    pub periods_remaining: u32,
+}
+
+#[event]
+pub struct SubscriptionPaused {
+    pub subscription: Pubkey,
+    pub client: Pubkey,
+}
+
+#[event]
+pub struct SubscriptionResumed {
+    pub subscription: Pubkey,
+    pub client: Pubkey,
+    pub next_claim_at: i64,
+}
+
+#[event]
+pub struct SubscriptionCancelled {
+    pub subscription: Pubkey,
+    pub client: Pubkey,
+    /// Elapsed-but-unclaimed periods paid out to the agent on cancel
+    pub agent_payout: u64,
+    pub refund_lamports: u64,
+}
+
+#[event]
+pub struct StreamWithdrawn {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub amount: u64,
+    pub withdrawn_total: u64,
+}
+
+#[event]
+pub struct StreamStopped {
+    pub job: Pubkey,
+    pub client: Pubkey,
+    /// Vested lamports the agent had not yet withdrawn
+    pub agent_payout: u64,
+    pub refund_lamports: u64,
+}
+
+#[event]
+pub struct ResultCommitted {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub result_hash: [u8; 32],
+}
+
+#[event]
+pub struct EncryptionKeyUpdated {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub encryption_key: Option<[u8; 32]>,
+}
+
+#[event]
+pub struct TeamJobCreated {
+    pub job: Pubkey,
+    pub client: Pubkey,
+    /// Member agent profiles, lead first
+    pub members: Vec<Pubkey>,
+    pub shares_bps: Vec<u16>,
+    pub escrow_lamports: u64,
+    pub require_all_signoff: bool,
+}
+
+#[event]
+pub struct TeamMemberSignedOff {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub pending_signoffs: u8,
+}
+
+#[event]
+pub struct JobBatchCreated {
+    pub group_id: Pubkey,
+    pub client: Pubkey,
+    pub jobs: Vec<Pubkey>,
+    pub total_escrow_lamports: u64,
+}
+
+#[event]
+pub struct PartialReleaseProposed {
+    pub job: Pubkey,
+    pub client: Pubkey,
+    pub agent_lamports: u64,
+    pub refund_lamports: u64,
+}
+
+#[event]
+pub struct PartialReleaseAccepted {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub agent_lamports: u64,
+    pub refund_lamports: u64,
+}
+
+#[event]
+pub struct JobReassigned {
+    pub job: Pubkey,
+    pub client: Pubkey,
+    pub previous_agent: Pubkey,
+    pub new_agent: Pubkey,
+    pub new_agent_profile: Pubkey,
+}
+
+#[event]
+pub struct ChildResultRecorded {
+    pub parent_job: Pubkey,
+    pub child_job: Pubkey,
+    pub result_hash: [u8; 32],
+    /// Parent's running hash after folding in this child
+    pub child_results_hash: [u8; 32],
+}
+
+#[event]
+pub struct AgentCapacityUpdated {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub max_concurrent_jobs: u16,
+    pub active_jobs: u16,
+}
+
+#[event]
+pub struct PriceTableUpdated {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub price_table: Vec<CapabilityPrice>,
+}
+
+#[event]
+pub struct AgentKeysUpdated {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub payout: Pubkey,
+    pub operator: Option<Pubkey>,
+}
+
+#[event]
+pub struct AgentTransferProposed {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Option<Pubkey>,
+}
+
+#[event]
+pub struct AgentTransferred {
+    pub agent: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct AgentMetadataUpdated {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub endpoint_uri: String,
+    pub metadata_uri: String,
+    pub metadata_hash: [u8; 32],
+    pub input_formats: u16,
+    pub schema_version: u8,
+}
+
+#[event]
+pub struct AccessListUpdated {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub mode: AccessMode,
+    pub clients: Vec<Pubkey>,
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::AgentProfile;
+use crate::error::AgentProtocolError;
+use crate::events::AgentTransferred;
+
+#[derive(Accounts)]
+pub struct AcceptAgentTransfer<'info> {
+    pub new_owner: Signer<'info>,
+    #[account(mut)]
+    pub agent_profile: Account<'info, AgentProfile>,
+}
+
+/// The proposed key takes over the profile, keeping its address, reputation
+/// and stake, which reference the unchanging `agent_id`. Payouts move to the
+/// new owner and the operator is cleared, so the previous owner keeps no hold
+/// on the agent. The agent must have no jobs in flight: child jobs it delegated
+/// name the old owner or operator as their client, and only that key could
+/// settle them after the transfer.
+pub fn handler(ctx: Context<AcceptAgentTransfer>) -> Result<()> {
+    let profile = &mut ctx.accounts.agent_profile;
+    let pending_owner = profile.pending_owner.ok_or(AgentProtocolError::NoPendingTransfer)?;
+    let new_owner = ctx.accounts.new_owner.key();
+    require!(pending_owner == new_owner, AgentProtocolError::Unauthorized);
+    require!(profile.active_jobs == 0, AgentProtocolError::AgentHasActiveJobs);
+
+    let previous_owner = profile.owner;
+    profile.owner = new_owner;
+    profile.payout = new_owner;
+    profile.operator = None;
+    profile.pending_owner = None;
+
+    emit!(AgentTransferred {
+        agent: profile.key(),
+        previous_owner,
+        new_owner,
+    });
+
+    Ok(())
+}
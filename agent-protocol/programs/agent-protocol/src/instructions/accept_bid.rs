@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{AgentProfile, Bid, Job, JobCounter, JobRequest, JobStatus};
+use crate::constants::DEFAULT_SLASH_BPS;
+use crate::error::AgentProtocolError;
+use crate::events::{BidAccepted, BidClosed, JobCreated};
+
+#[derive(Accounts)]
+pub struct AcceptBid<'info> {
+    #[account(
+        mut,
+        constraint = job_request.client == client.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        close = client
+    )]
+    pub job_request: Account<'info, JobRequest>,
+    #[account(
+        mut,
+        close = agent,
+        constraint = bid.job_request == job_request.key() @ AgentProtocolError::JobRequestMismatch,
+        constraint = bid.revealed @ AgentProtocolError::BidNotRevealed
+    )]
+    pub bid: Account<'info, Bid>,
+    /// CHECK: Winning bidder receives the bid's rent. Validated against bid.agent.
+    #[account(
+        mut,
+        constraint = bid.agent == agent.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.key() == bid.agent_profile @ AgentProtocolError::Unauthorized,
+        constraint = agent_profile.is_active @ AgentProtocolError::AgentNotActive
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + JobCounter::INIT_SPACE,
+        seeds = [b"job_counter", client.key().as_ref()],
+        bump
+    )]
+    pub job_counter: Account<'info, JobCounter>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Job::INIT_SPACE,
+        seeds = [
+            b"job",
+            client.key().as_ref(),
+            &job_counter.next_nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub job: Account<'info, Job>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Losing bids may be passed as remaining accounts in `[bid, bidder wallet]`
+/// pairs; each is closed with its rent returned to the bidder.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AcceptBid<'info>>,
+    auto_release_seconds: Option<i64>,
+) -> Result<()> {
+    let request = &ctx.accounts.job_request;
+    let clock = Clock::get()?;
+
+    // Sealed requests are only decided once every bidder had the chance to reveal
+    if let Some(reveal_deadline) = request.reveal_deadline {
+        require!(
+            clock.unix_timestamp > reveal_deadline,
+            AgentProtocolError::BiddingStillOpen
+        );
+    }
+
+    ctx.accounts.agent_profile.open_job()?;
+
+    let price_lamports = ctx.accounts.bid.price_lamports;
+
+    // Transfer the winning price from client to Job PDA (escrow)
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.client.to_account_info(),
+                to: ctx.accounts.job.to_account_info(),
+            },
+        ),
+        price_lamports,
+    )?;
+
+    let auto_release_at = auto_release_seconds.map(|s| clock.unix_timestamp + s);
+
+    let nonce = ctx.accounts.job_counter.take_nonce(
+        ctx.accounts.client.key(),
+        ctx.bumps.job_counter,
+    )?;
+
+    let job = &mut ctx.accounts.job;
+    job.client = ctx.accounts.client.key();
+    job.agent = ctx.accounts.agent_profile.agent_id;
+    job.escrow_lamports = price_lamports;
+    job.status = JobStatus::Pending;
+    job.title = request.title.clone();
+    job.spec = request.spec.clone();
+    job.result_uri = String::new();
+    job.result_hash = None;
+    job.parent_job = None;
+    job.active_children = 0;
+    job.auto_release_at = auto_release_at;
+    job.disputed_at = None;
+    job.created_at = clock.unix_timestamp;
+    job.completed_at = None;
+    job.nonce = nonce;
+    job.required_capabilities = 0;
+    job.min_rating_x100 = 0;
+    job.claim_deadline = None;
+    job.team_size = 0;
+    job.pending_signoffs = 0;
+    job.group_id = None;
+    job.bonus_lamports = 0;
+    job.partial_release_lamports = None;
+    job.original_escrow = price_lamports;
+    job.delegated_total = 0;
+    job.child_results_hash = [0u8; 32];
+    job.children_completed = 0;
+    job.price_tier = 0;
+    job.disputed_by = None;
+    job.slash_bps = DEFAULT_SLASH_BPS;
+    job.auto_release_delay = None;
+    job.has_extension = false;
+    job.bump = ctx.bumps.job;
+
+    emit!(JobCreated {
+        job: job.key(),
+        client: job.client,
+        agent: job.agent,
+        escrow_lamports: price_lamports,
+        auto_release_at,
+    });
+
+    emit!(BidAccepted {
+        job_request: request.key(),
+        bid: ctx.accounts.bid.key(),
+        job: job.key(),
+        agent: job.agent,
+        price_lamports,
+    });
+
+    // Close losing bids supplied by the client
+    require!(
+        ctx.remaining_accounts.len() % 2 == 0,
+        AgentProtocolError::JobRequestMismatch
+    );
+    for pair in ctx.remaining_accounts.chunks(2) {
+        let (bid_info, bidder_info) = (&pair[0], &pair[1]);
+        require!(
+            bid_info.key() != ctx.accounts.bid.key(),
+            AgentProtocolError::InvalidBid
+        );
+        let losing_bid = Account::<Bid>::try_from(bid_info)?;
+        require!(
+            losing_bid.job_request == request.key(),
+            AgentProtocolError::JobRequestMismatch
+        );
+        require!(
+            losing_bid.agent == bidder_info.key(),
+            AgentProtocolError::Unauthorized
+        );
+        losing_bid.close(bidder_info.clone())?;
+
+        emit!(BidClosed {
+            job_request: request.key(),
+            bid: bid_info.key(),
+            agent: bidder_info.key(),
+        });
+    }
+
+    emit!(BidClosed {
+        job_request: request.key(),
+        bid: ctx.accounts.bid.key(),
+        agent: ctx.accounts.agent.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, Job, JobStatus, JobTeam};
+use crate::error::AgentProtocolError;
+use crate::events::{ChildResultRecorded, PartialReleaseAccepted, PaymentReleased};
+use super::release_payment::pay_team_members;
+
+#[derive(Accounts)]
+pub struct AcceptPartialRelease<'info> {
+    #[account(
+        constraint = agent_profile.owner == agent.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent: Signer<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    /// CHECK: Agent payout wallet receives the agreed amount. Validated against agent_profile.payout.
+    #[account(
+        mut,
+        constraint = agent_profile.payout == payout.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub payout: AccountInfo<'info>,
+    #[account(mut)]
+    pub job: Account<'info, Job>,
+    /// CHECK: Client receives the refund (the parent job does for child jobs). Validated against job.client.
+    #[account(
+        mut,
+        constraint = job.client == client.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub client: AccountInfo<'info>,
+    /// Optional parent job — required when job.parent_job is Some
+    #[account(mut)]
+    pub parent_job: Option<Account<'info, Job>>,
+    /// Team roster — required when job.team_size > 0; rent returns to the client
+    #[account(
+        mut,
+        seeds = [b"team", job.key().as_ref()],
+        bump = team.bump,
+        close = client
+    )]
+    pub team: Option<Account<'info, JobTeam>>,
+}
+
+/// The agent passes back the amount it agrees to, so a client cannot swap in
+/// a lower proposal ahead of the acceptance. Team members are passed as
+/// remaining accounts, as in release_payment, and split the agreed amount.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AcceptPartialRelease<'info>>,
+    agent_lamports: u64,
+) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+    require!(
+        job.status == JobStatus::InProgress || job.status == JobStatus::Completed,
+        AgentProtocolError::InvalidJobStatus
+    );
+    require!(job.active_children == 0, AgentProtocolError::UnresolvedChildren);
+    let proposed = job.partial_release_lamports
+        .ok_or(AgentProtocolError::NoPartialReleaseProposal)?;
+    require!(proposed == agent_lamports, AgentProtocolError::PartialReleaseMismatch);
+
+    // Refund the client's part first; the agreed amount becomes the escrow.
+    // A child job's unspent escrow goes back into its parent.
+    let refund_amount = job.escrow_lamports
+        .checked_sub(agent_lamports)
+        .ok_or(AgentProtocolError::InsufficientEscrow)?;
+    let refund_info = match job.parent_job {
+        Some(parent_key) => {
+            let parent = ctx.accounts.parent_job.as_mut()
+                .ok_or(AgentProtocolError::ParentJobMismatch)?;
+            require!(parent_key == parent.key(), AgentProtocolError::ParentJobMismatch);
+            parent.return_child_escrow(refund_amount)?;
+            parent.to_account_info()
+        }
+        None => ctx.accounts.client.to_account_info(),
+    };
+    let job_info = job.to_account_info();
+    **job_info.try_borrow_mut_lamports()? -= refund_amount;
+    **refund_info.try_borrow_mut_lamports()? += refund_amount;
+    job.escrow_lamports = agent_lamports;
+
+    // Team members are paid first; the lead (fixed accounts) takes the remainder
+    let (agent_amount, _) = pay_team_members(
+        &ctx.accounts.job,
+        ctx.accounts.team.as_deref(),
+        &ctx.accounts.agent_profile,
+        ctx.remaining_accounts,
+        false,
+    )?;
+
+    let job = &mut ctx.accounts.job;
+    let margin_lamports = job.delegation_margin(agent_amount);
+
+    // Terminal state
+    job.status = JobStatus::Finalized;
+    job.escrow_lamports = 0;
+    if job.completed_at.is_none() {
+        job.completed_at = Some(Clock::get()?.unix_timestamp);
+    }
+
+    // Transfer the agreed amount to agent via direct lamport manipulation
+    let agent_info = ctx.accounts.payout.to_account_info();
+    **job_info.try_borrow_mut_lamports()? -= agent_amount;
+    **agent_info.try_borrow_mut_lamports()? += agent_amount;
+
+    // Update agent stats
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.jobs_completed = profile.jobs_completed
+        .checked_add(1)
+        .ok_or(AgentProtocolError::Overflow)?;
+    profile.close_job()?;
+    profile.record_earnings(agent_amount)?;
+
+    // Handle parent decrement for child jobs
+    if job.parent_job.is_some() {
+        let parent = ctx.accounts.parent_job.as_mut()
+            .ok_or(AgentProtocolError::ParentJobMismatch)?;
+        require!(
+            job.parent_job.unwrap() == parent.key(),
+            AgentProtocolError::ParentJobMismatch
+        );
+        require!(parent.active_children > 0, AgentProtocolError::Overflow);
+        parent.active_children = parent.active_children
+            .checked_sub(1)
+            .ok_or(AgentProtocolError::Overflow)?;
+
+        // Link the child's result into the parent for auditing the tree
+        let result_hash = job.result_hash.unwrap_or_default();
+        parent.record_child_result(job.key(), result_hash)?;
+        emit!(ChildResultRecorded {
+            parent_job: parent.key(),
+            child_job: job.key(),
+            result_hash,
+            child_results_hash: parent.child_results_hash,
+        });
+    }
+
+    emit!(PaymentReleased {
+        job: ctx.accounts.job.key(),
+        agent: ctx.accounts.job.agent,
+        amount: agent_amount,
+        bonus: 0,
+        auto_released: false,
+        margin_lamports,
+    });
+    emit!(PartialReleaseAccepted {
+        job: ctx.accounts.job.key(),
+        agent: ctx.accounts.job.agent,
+        agent_lamports,
+        refund_lamports: refund_amount,
+    });
+
+    Ok(())
+}
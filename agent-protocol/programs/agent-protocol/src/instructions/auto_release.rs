@@ -1,19 +1,20 @@
 use anchor_lang::prelude::*;
-use crate::state::{AgentProfile, Job, JobStatus};
+use crate::state::{AgentProfile, Job, JobStatus, JobTeam};
 use crate::error::AgentProtocolError;
-use crate::events::PaymentReleased;
+use crate::events::{ChildResultRecorded, PaymentReleased};
+use super::release_payment::pay_team_members;
 
 #[derive(Accounts)]
 pub struct AutoRelease<'info> {
-    /// CHECK: Agent wallet receives payment. Validated against job.agent.
+    /// CHECK: Agent payout wallet receives payment. Validated against agent_profile.payout.
     #[account(
         mut,
-        constraint = job.agent == agent.key() @ AgentProtocolError::Unauthorized
+        constraint = agent_profile.payout == agent.key() @ AgentProtocolError::Unauthorized
     )]
     pub agent: AccountInfo<'info>,
     #[account(
         mut,
-        constraint = agent_profile.owner == job.agent @ AgentProtocolError::Unauthorized
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized
     )]
     pub agent_profile: Account<'info, AgentProfile>,
     #[account(mut)]
@@ -27,10 +28,19 @@ pub struct AutoRelease<'info> {
     /// Optional parent job — required when job.parent_job is Some
     #[account(mut)]
     pub parent_job: Option<Account<'info, Job>>,
+    /// Team roster — required when job.team_size > 0; rent returns to the client
+    #[account(
+        mut,
+        seeds = [b"team", job.key().as_ref()],
+        bump = team.bump,
+        close = client
+    )]
+    pub team: Option<Account<'info, JobTeam>>,
 }
 
-pub fn handler(ctx: Context<AutoRelease>) -> Result<()> {
-    let job = &mut ctx.accounts.job;
+/// Team members are passed as remaining accounts, as in release_payment.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, AutoRelease<'info>>) -> Result<()> {
+    let job = &ctx.accounts.job;
     require!(job.status == JobStatus::Completed, AgentProtocolError::InvalidJobStatus);
     require!(job.auto_release_at.is_some(), AgentProtocolError::NoAutoRelease);
 
@@ -40,7 +50,17 @@ pub fn handler(ctx: Context<AutoRelease>) -> Result<()> {
         AgentProtocolError::AutoReleaseNotReady
     );
 
-    let escrow_amount = job.escrow_lamports;
+    // Team members are paid first; the lead (fixed accounts) takes the remainder
+    let (agent_amount, _) = pay_team_members(
+        job,
+        ctx.accounts.team.as_deref(),
+        &ctx.accounts.agent_profile,
+        ctx.remaining_accounts,
+        true,
+    )?;
+
+    let job = &mut ctx.accounts.job;
+    let margin_lamports = job.delegation_margin(agent_amount);
 
     // Terminal state — account will be closed in same instruction
     job.status = JobStatus::Finalized;
@@ -49,14 +69,16 @@ pub fn handler(ctx: Context<AutoRelease>) -> Result<()> {
     // Transfer escrow to agent via direct lamport manipulation
     let job_info = job.to_account_info();
     let agent_info = ctx.accounts.agent.to_account_info();
-    **job_info.try_borrow_mut_lamports()? -= escrow_amount;
-    **agent_info.try_borrow_mut_lamports()? += escrow_amount;
+    **job_info.try_borrow_mut_lamports()? -= agent_amount;
+    **agent_info.try_borrow_mut_lamports()? += agent_amount;
 
     // Update agent stats
     let profile = &mut ctx.accounts.agent_profile;
     profile.jobs_completed = profile.jobs_completed
         .checked_add(1)
         .ok_or(AgentProtocolError::Overflow)?;
+    profile.close_job()?;
+    profile.record_earnings(agent_amount)?;
 
     // Handle parent decrement for child jobs
     if job.parent_job.is_some() {
@@ -70,13 +92,26 @@ pub fn handler(ctx: Context<AutoRelease>) -> Result<()> {
         parent.active_children = parent.active_children
             .checked_sub(1)
             .ok_or(AgentProtocolError::Overflow)?;
+
+        // Link the child's result into the parent for auditing the tree
+        let result_hash = job.result_hash.unwrap_or_default();
+        parent.record_child_result(job.key(), result_hash)?;
+        emit!(ChildResultRecorded {
+            parent_job: parent.key(),
+            child_job: job.key(),
+            result_hash,
+            child_results_hash: parent.child_results_hash,
+        });
     }
 
     emit!(PaymentReleased {
         job: ctx.accounts.job.key(),
-        agent: ctx.accounts.agent.key(),
-        amount: escrow_amount,
+        agent: ctx.accounts.job.agent,
+        amount: agent_amount,
+        // Bonuses are only attached by a client-signed release
+        bonus: 0,
         auto_released: true,
+        margin_lamports,
     });
 
     Ok(())
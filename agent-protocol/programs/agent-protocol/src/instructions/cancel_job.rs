@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::{Job, JobStatus};
+use crate::state::{AgentProfile, Job, JobExtension, JobStatus, JobTeam};
 use crate::error::AgentProtocolError;
 use crate::events::JobCancelled;
 
@@ -15,11 +15,61 @@ pub struct CancelJob<'info> {
         close = client
     )]
     pub job: Account<'info, Job>,
+    /// Assigned agent's profile — required unless the job is an unclaimed bounty
+    #[account(
+        mut,
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Option<Account<'info, AgentProfile>>,
+    /// Optional parent job — required when job.parent_job is Some
+    #[account(mut)]
+    pub parent_job: Option<Account<'info, Job>>,
+    /// Team roster — required when job.team_size > 0; rent returns to the client
+    #[account(
+        mut,
+        seeds = [b"team", job.key().as_ref()],
+        bump = team.bump,
+        close = client
+    )]
+    pub team: Option<Account<'info, JobTeam>>,
+    /// Extension PDA — required when job.has_extension; rent returns to the client
+    #[account(
+        mut,
+        seeds = [b"job_ext", job.key().as_ref()],
+        bump = job_extension.bump,
+        close = client
+    )]
+    pub job_extension: Option<Account<'info, JobExtension>>,
 }
 
 pub fn handler(ctx: Context<CancelJob>) -> Result<()> {
     let job = &mut ctx.accounts.job;
-    require!(job.status == JobStatus::Pending, AgentProtocolError::InvalidJobStatus);
+    // Unclaimed bounties can be withdrawn like any job the agent has not started
+    require!(
+        job.status == JobStatus::Pending || job.status == JobStatus::Open,
+        AgentProtocolError::InvalidJobStatus
+    );
+    // The roster is closed with the job, so it has to be passed in
+    require!(
+        job.team_size == 0 || ctx.accounts.team.is_some(),
+        AgentProtocolError::TeamMismatch
+    );
+    // The extension is closed with the job, so it has to be passed in
+    job.check_extension(ctx.accounts.job_extension.as_deref())?;
+    // Once a stream starts, vested lamports belong to the agent — use stop_stream
+    if let Some(stream) = ctx.accounts.job_extension.as_ref().and_then(|extension| extension.stream) {
+        require!(
+            Clock::get()?.unix_timestamp < stream.start_at,
+            AgentProtocolError::StreamingJob
+        );
+    }
+
+    // Free the agent's concurrency slot; open bounties never took one
+    if job.status == JobStatus::Pending {
+        ctx.accounts.agent_profile.as_mut()
+            .ok_or(AgentProtocolError::Unauthorized)?
+            .close_job()?;
+    }
 
     let refund_amount = job.escrow_lamports;
 
@@ -27,11 +77,23 @@ pub fn handler(ctx: Context<CancelJob>) -> Result<()> {
     job.status = JobStatus::Cancelled;
     job.escrow_lamports = 0;
 
-    // Refund escrow to client via direct lamport manipulation
+    // A child's escrow was carved out of its parent, so it goes back there
+    // rather than to whichever key delegated it
+    let refund_info = match job.parent_job {
+        Some(parent_key) => {
+            let parent = ctx.accounts.parent_job.as_mut()
+                .ok_or(AgentProtocolError::ParentJobMismatch)?;
+            require!(parent_key == parent.key(), AgentProtocolError::ParentJobMismatch);
+            parent.reclaim_child(refund_amount)?;
+            parent.to_account_info()
+        }
+        None => ctx.accounts.client.to_account_info(),
+    };
+
+    // Refund escrow via direct lamport manipulation
     let job_info = job.to_account_info();
-    let client_info = ctx.accounts.client.to_account_info();
     **job_info.try_borrow_mut_lamports()? -= refund_amount;
-    **client_info.try_borrow_mut_lamports()? += refund_amount;
+    **refund_info.try_borrow_mut_lamports()? += refund_amount;
 
     emit!(JobCancelled {
         job: ctx.accounts.job.key(),
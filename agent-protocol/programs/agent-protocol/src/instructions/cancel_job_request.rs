@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::state::JobRequest;
+use crate::error::AgentProtocolError;
+use crate::events::JobRequestCancelled;
+
+#[derive(Accounts)]
+pub struct CancelJobRequest<'info> {
+    #[account(
+        mut,
+        constraint = job_request.client == client.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        close = client
+    )]
+    pub job_request: Account<'info, JobRequest>,
+}
+
+/// Nothing is escrowed until a bid is accepted, so cancelling only returns rent.
+/// Bidders reclaim their bid rent via close_bid.
+pub fn handler(ctx: Context<CancelJobRequest>) -> Result<()> {
+    emit!(JobRequestCancelled {
+        job_request: ctx.accounts.job_request.key(),
+        client: ctx.accounts.client.key(),
+    });
+
+    Ok(())
+}
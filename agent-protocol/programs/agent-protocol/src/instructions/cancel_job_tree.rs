@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, Job, JobExtension, JobStatus, JobTeam};
+use crate::error::AgentProtocolError;
+use crate::events::JobCancelled;
+
+#[derive(Accounts)]
+pub struct CancelJobTree<'info> {
+    #[account(
+        mut,
+        constraint = job.client == client.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        close = client
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    /// Optional parent job — required when job.parent_job is Some
+    #[account(mut)]
+    pub parent_job: Option<Account<'info, Job>>,
+    /// Team roster — required when job.team_size > 0; rent returns to the client
+    #[account(
+        mut,
+        seeds = [b"team", job.key().as_ref()],
+        bump = team.bump,
+        close = client
+    )]
+    pub team: Option<Account<'info, JobTeam>>,
+    /// Extension PDA — required when job.has_extension; rent returns to the client
+    #[account(
+        mut,
+        seeds = [b"job_ext", job.key().as_ref()],
+        bump = job_extension.bump,
+        close = client
+    )]
+    pub job_extension: Option<Account<'info, JobExtension>>,
+}
+
+/// Every active child is passed in the remaining accounts as a `[child_job,
+/// sub_agent_profile, child_client]` triple and must still be Pending.
+/// Children are refunded into the parent and closed, their rent going back to
+/// whichever key delegated each one (owner, operator or a former owner), then
+/// the whole escrow goes back to the client (or, for a child job, to its own
+/// parent) as in cancel_job.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, CancelJobTree<'info>>) -> Result<()> {
+    let job = &ctx.accounts.job;
+    // Delegation is the only progress that may be unwound here
+    require!(
+        job.status == JobStatus::Pending
+            || (job.status == JobStatus::InProgress && job.active_children > 0),
+        AgentProtocolError::InvalidJobStatus
+    );
+    require!(
+        job.result_uri.is_empty() && job.result_hash.is_none(),
+        AgentProtocolError::ResultAlreadyCommitted
+    );
+    // The roster is closed with the job, so it has to be passed in
+    require!(
+        job.team_size == 0 || ctx.accounts.team.is_some(),
+        AgentProtocolError::TeamMismatch
+    );
+    // The extension is closed with the job, so it has to be passed in
+    job.check_extension(ctx.accounts.job_extension.as_deref())?;
+    // Once a stream starts, vested lamports belong to the agent — use stop_stream
+    if let Some(stream) = ctx.accounts.job_extension.as_ref().and_then(|extension| extension.stream) {
+        require!(
+            Clock::get()?.unix_timestamp < stream.start_at,
+            AgentProtocolError::StreamingJob
+        );
+    }
+    require!(
+        ctx.remaining_accounts.len() == job.active_children as usize * 3,
+        AgentProtocolError::UnresolvedChildren
+    );
+
+    let job_info = job.to_account_info();
+    let mut recovered: u64 = 0;
+    for triple in ctx.remaining_accounts.chunks(3) {
+        let (child_info, profile_info, rent_info) = (&triple[0], &triple[1], &triple[2]);
+        // A child passed twice fails here, as the first pass closed it
+        let mut child = Account::<Job>::try_from(child_info)?;
+        require!(
+            child.parent_job == Some(job.key()),
+            AgentProtocolError::ParentJobMismatch
+        );
+        require!(child.status == JobStatus::Pending, AgentProtocolError::InvalidJobStatus);
+        require!(child.client == rent_info.key(), AgentProtocolError::Unauthorized);
+
+        // Free the sub-agent's concurrency slot. An agent that hired itself is
+        // updated through the fixed account so the two copies cannot diverge.
+        if profile_info.key() == ctx.accounts.agent_profile.key() {
+            require!(child.agent == job.agent, AgentProtocolError::Unauthorized);
+            ctx.accounts.agent_profile.close_job()?;
+        } else {
+            let mut sub_agent = Account::<AgentProfile>::try_from(profile_info)?;
+            require!(sub_agent.agent_id == child.agent, AgentProtocolError::Unauthorized);
+            sub_agent.close_job()?;
+            sub_agent.exit(&crate::ID)?;
+        }
+
+        let refund_amount = child.escrow_lamports;
+        child.status = JobStatus::Cancelled;
+        child.escrow_lamports = 0;
+
+        // Return the child's escrow to the parent via direct lamport manipulation
+        **child_info.try_borrow_mut_lamports()? -= refund_amount;
+        **job_info.try_borrow_mut_lamports()? += refund_amount;
+        recovered = recovered
+            .checked_add(refund_amount)
+            .ok_or(AgentProtocolError::Overflow)?;
+
+        emit!(JobCancelled {
+            job: child.key(),
+            client: child.client,
+            refund_lamports: refund_amount,
+        });
+
+        child.close(rent_info.clone())?;
+    }
+
+    let job = &mut ctx.accounts.job;
+    let refund_amount = job.escrow_lamports
+        .checked_add(recovered)
+        .ok_or(AgentProtocolError::Overflow)?;
+
+    // Terminal state — account will be closed in same instruction
+    job.status = JobStatus::Cancelled;
+    job.escrow_lamports = 0;
+    job.active_children = 0;
+    ctx.accounts.agent_profile.close_job()?;
+
+    // A subtree's escrow goes back into the job it was delegated from
+    let refund_info = match job.parent_job {
+        Some(parent_key) => {
+            let parent = ctx.accounts.parent_job.as_mut()
+                .ok_or(AgentProtocolError::ParentJobMismatch)?;
+            require!(parent_key == parent.key(), AgentProtocolError::ParentJobMismatch);
+            parent.reclaim_child(refund_amount)?;
+            parent.to_account_info()
+        }
+        None => ctx.accounts.client.to_account_info(),
+    };
+
+    // Refund the whole escrow via direct lamport manipulation
+    **job_info.try_borrow_mut_lamports()? -= refund_amount;
+    **refund_info.try_borrow_mut_lamports()? += refund_amount;
+
+    emit!(JobCancelled {
+        job: ctx.accounts.job.key(),
+        client: ctx.accounts.client.key(),
+        refund_lamports: refund_amount,
+    });
+
+    Ok(())
+}
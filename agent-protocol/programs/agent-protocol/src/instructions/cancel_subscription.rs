@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, Subscription};
+use crate::error::AgentProtocolError;
+use crate::events::SubscriptionCancelled;
+
+#[derive(Accounts)]
+pub struct CancelSubscription<'info> {
+    #[account(
+        mut,
+        constraint = subscription.client == client.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.key() == subscription.agent_profile @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    /// CHECK: Agent payout wallet receives already-earned periods. Validated against agent_profile.payout.
+    #[account(
+        mut,
+        constraint = agent_profile.payout == agent.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent: AccountInfo<'info>,
+    #[account(
+        mut,
+        close = client
+    )]
+    pub subscription: Account<'info, Subscription>,
+}
+
+pub fn handler(ctx: Context<CancelSubscription>) -> Result<()> {
+    let sub = &mut ctx.accounts.subscription;
+
+    // Periods that already elapsed were earned; everything after is refunded
+    let earned_periods = sub.claimable_periods(Clock::get()?.unix_timestamp);
+    let unclaimed_periods = sub.total_periods - sub.claimed_periods;
+    let agent_payout = sub.amount_per_period
+        .checked_mul(earned_periods as u64)
+        .ok_or(AgentProtocolError::Overflow)?;
+    let refund_amount = sub.amount_per_period
+        .checked_mul((unclaimed_periods - earned_periods) as u64)
+        .ok_or(AgentProtocolError::Overflow)?;
+
+    // Terminal state — account will be closed in same instruction
+    sub.claimed_periods = sub.total_periods;
+
+    let sub_info = sub.to_account_info();
+    let agent_info = ctx.accounts.agent.to_account_info();
+    let client_info = ctx.accounts.client.to_account_info();
+    **sub_info.try_borrow_mut_lamports()? -= agent_payout;
+    **agent_info.try_borrow_mut_lamports()? += agent_payout;
+    **sub_info.try_borrow_mut_lamports()? -= refund_amount;
+    **client_info.try_borrow_mut_lamports()? += refund_amount;
+
+    // Earned periods count the same as if claim_period had paid them
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.jobs_completed = profile.jobs_completed
+        .checked_add(earned_periods)
+        .ok_or(AgentProtocolError::Overflow)?;
+    profile.record_earnings(agent_payout)?;
+
+    emit!(SubscriptionCancelled {
+        subscription: sub.key(),
+        client: ctx.accounts.client.key(),
+        agent_payout,
+        refund_lamports: refund_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, Job, JobStatus};
+use crate::error::AgentProtocolError;
+use crate::events::BountyClaimed;
+
+#[derive(Accounts)]
+pub struct ClaimBounty<'info> {
+    pub agent: Signer<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.owner == agent.key() @ AgentProtocolError::Unauthorized,
+        constraint = agent_profile.is_active @ AgentProtocolError::AgentNotActive
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    #[account(
+        mut,
+        constraint = job.status == JobStatus::Open @ AgentProtocolError::InvalidJobStatus
+    )]
+    pub job: Account<'info, Job>,
+}
+
+pub fn handler(ctx: Context<ClaimBounty>) -> Result<()> {
+    let profile = &mut ctx.accounts.agent_profile;
+    let job = &mut ctx.accounts.job;
+
+    let now = Clock::get()?.unix_timestamp;
+    if let Some(deadline) = job.claim_deadline {
+        require!(
+            now <= deadline,
+            AgentProtocolError::BountyExpired
+        );
+    }
+    require!(
+        profile.capabilities & job.required_capabilities == job.required_capabilities,
+        AgentProtocolError::CapabilityMismatch
+    );
+    require!(
+        profile.rating_avg_x100() >= job.min_rating_x100 as u64,
+        AgentProtocolError::RatingTooLow
+    );
+    // Agents keep their price floor on bounties too
+    require!(
+        job.escrow_lamports >= profile.price_for(job.required_capabilities, job.price_tier),
+        AgentProtocolError::InsufficientPayment
+    );
+
+    profile.open_job()?;
+
+    // From here on the normal update_job / release_payment flow applies
+    job.agent = profile.agent_id;
+    job.status = JobStatus::Pending;
+    job.auto_release_at = job.auto_release_delay.map(|s| now + s);
+
+    emit!(BountyClaimed {
+        job: job.key(),
+        agent: profile.agent_id,
+        agent_profile: profile.key(),
+    });
+
+    Ok(())
+}
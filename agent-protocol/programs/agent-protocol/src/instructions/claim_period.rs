@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, Subscription};
+use crate::error::AgentProtocolError;
+use crate::events::SubscriptionPeriodClaimed;
+
+#[derive(Accounts)]
+pub struct ClaimPeriod<'info> {
+    #[account(
+        constraint = agent_profile.owner == agent.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent: Signer<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.key() == subscription.agent_profile @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    /// CHECK: Agent payout wallet receives the period. Validated against agent_profile.payout.
+    #[account(
+        mut,
+        constraint = agent_profile.payout == payout.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub payout: AccountInfo<'info>,
+    #[account(mut)]
+    pub subscription: Account<'info, Subscription>,
+}
+
+pub fn handler(ctx: Context<ClaimPeriod>) -> Result<()> {
+    let sub = &mut ctx.accounts.subscription;
+    require!(
+        sub.claimed_periods < sub.total_periods,
+        AgentProtocolError::SubscriptionComplete
+    );
+    require!(
+        sub.claimable_periods(Clock::get()?.unix_timestamp) > 0,
+        AgentProtocolError::PeriodNotElapsed
+    );
+
+    let amount = sub.amount_per_period;
+
+    // Advance the schedule before moving lamports
+    sub.claimed_periods += 1;
+    sub.next_claim_at = sub.next_claim_at
+        .checked_add(sub.period_seconds)
+        .ok_or(AgentProtocolError::Overflow)?;
+
+    // Pay one period via direct lamport manipulation
+    let sub_info = sub.to_account_info();
+    let agent_info = ctx.accounts.payout.to_account_info();
+    **sub_info.try_borrow_mut_lamports()? -= amount;
+    **agent_info.try_borrow_mut_lamports()? += amount;
+
+    // Each delivered period counts as a completed job
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.jobs_completed = profile.jobs_completed
+        .checked_add(1)
+        .ok_or(AgentProtocolError::Overflow)?;
+    profile.record_earnings(amount)?;
+
+    emit!(SubscriptionPeriodClaimed {
+        subscription: sub.key(),
+        agent: sub.agent,
+        amount,
+        period: sub.claimed_periods,
+        periods_remaining: sub.total_periods - sub.claimed_periods,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::state::Bid;
+use crate::error::AgentProtocolError;
+use crate::events::BidClosed;
+
+#[derive(Accounts)]
+pub struct CloseBid<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+    #[account(
+        mut,
+        close = agent,
+        constraint = bid.agent == agent.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub bid: Account<'info, Bid>,
+}
+
+/// Withdraws a bid, or reclaims its rent once the request is awarded or cancelled.
+pub fn handler(ctx: Context<CloseBid>) -> Result<()> {
+    emit!(BidClosed {
+        job_request: ctx.accounts.bid.job_request,
+        bid: ctx.accounts.bid.key(),
+        agent: ctx.accounts.agent.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, Job, JobStatus};
+use crate::error::AgentProtocolError;
+use crate::events::ResultCommitted;
+
+#[derive(Accounts)]
+pub struct CommitResult<'info> {
+    /// The assigned agent's owner or operator
+    pub agent: Signer<'info>,
+    #[account(mut)]
+    pub job: Account<'info, Job>,
+    #[account(
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized,
+        constraint = agent_profile.can_operate(agent.key()) @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+}
+
+/// First half of commit-reveal delivery: the agent pins the deliverable's hash
+/// and the job moves to InProgress, so the client can no longer cancel. The URI
+/// is revealed later through update_job, which must present the same hash.
+pub fn handler(ctx: Context<CommitResult>, result_hash: [u8; 32]) -> Result<()> {
+    require!(result_hash != [0u8; 32], AgentProtocolError::EmptyResultHash);
+
+    let job = &mut ctx.accounts.job;
+    require!(
+        job.status == JobStatus::Pending || job.status == JobStatus::InProgress,
+        AgentProtocolError::InvalidJobStatus
+    );
+    require!(job.result_hash.is_none(), AgentProtocolError::ResultAlreadyCommitted);
+
+    job.result_hash = Some(result_hash);
+    if job.status == JobStatus::Pending {
+        job.status = JobStatus::InProgress;
+    }
+
+    emit!(ResultCommitted {
+        job: job.key(),
+        agent: job.agent,
+        result_hash,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use crate::state::{validate_title_and_spec, JobCounter, JobRequest, JobSpec};
+use crate::error::AgentProtocolError;
+use crate::events::JobRequestCreated;
+
+#[derive(Accounts)]
+pub struct CreateJobRequest<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + JobCounter::INIT_SPACE,
+        seeds = [b"job_counter", client.key().as_ref()],
+        bump
+    )]
+    pub job_counter: Account<'info, JobCounter>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + JobRequest::INIT_SPACE,
+        seeds = [
+            b"job_request",
+            client.key().as_ref(),
+            &job_counter.next_nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub job_request: Account<'info, JobRequest>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreateJobRequest>,
+    title: String,
+    max_budget_lamports: u64,
+    bidding_seconds: i64,
+    reveal_seconds: Option<i64>,
+    spec: Option<JobSpec>,
+) -> Result<()> {
+    validate_title_and_spec(&title, spec.as_ref())?;
+    require!(max_budget_lamports > 0, AgentProtocolError::InvalidPrice);
+    require!(bidding_seconds > 0, AgentProtocolError::InvalidDuration);
+    if let Some(reveal) = reveal_seconds {
+        require!(reveal > 0, AgentProtocolError::InvalidDuration);
+    }
+
+    let clock = Clock::get()?;
+    let bid_deadline = clock.unix_timestamp
+        .checked_add(bidding_seconds)
+        .ok_or(AgentProtocolError::Overflow)?;
+    // A reveal window makes the request sealed
+    let reveal_deadline = match reveal_seconds {
+        Some(s) => Some(bid_deadline.checked_add(s).ok_or(AgentProtocolError::Overflow)?),
+        None => None,
+    };
+
+    let nonce = ctx.accounts.job_counter.take_nonce(
+        ctx.accounts.client.key(),
+        ctx.bumps.job_counter,
+    )?;
+
+    let request = &mut ctx.accounts.job_request;
+    request.client = ctx.accounts.client.key();
+    request.title = title;
+    request.spec = spec;
+    request.max_budget_lamports = max_budget_lamports;
+    request.sealed = reveal_deadline.is_some();
+    request.bid_deadline = bid_deadline;
+    request.reveal_deadline = reveal_deadline;
+    request.bid_count = 0;
+    request.created_at = clock.unix_timestamp;
+    request.nonce = nonce;
+    request.bump = ctx.bumps.job_request;
+
+    emit!(JobRequestCreated {
+        job_request: request.key(),
+        client: request.client,
+        max_budget_lamports,
+        sealed: request.sealed,
+        bid_deadline,
+        reveal_deadline,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{AgentAccessList, AgentProfile, JobCounter, Subscription};
+use crate::error::AgentProtocolError;
+use crate::events::SubscriptionCreated;
+
+#[derive(Accounts)]
+pub struct CreateSubscription<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(
+        constraint = agent_profile.is_active @ AgentProtocolError::AgentNotActive
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + JobCounter::INIT_SPACE,
+        seeds = [b"job_counter", client.key().as_ref()],
+        bump
+    )]
+    pub job_counter: Account<'info, JobCounter>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Subscription::INIT_SPACE,
+        seeds = [
+            b"subscription",
+            client.key().as_ref(),
+            &job_counter.next_nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+    /// CHECK: Agent's access list. Enforced only if initialized.
+    #[account(
+        seeds = [b"access", agent_profile.key().as_ref()],
+        bump
+    )]
+    pub access_list: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreateSubscription>,
+    amount_per_period: u64,
+    period_seconds: i64,
+    total_periods: u32,
+) -> Result<()> {
+    AgentAccessList::check(&ctx.accounts.access_list, &ctx.accounts.client.key())?;
+    require!(period_seconds > 0, AgentProtocolError::InvalidDuration);
+    require!(total_periods > 0, AgentProtocolError::InvalidDuration);
+    // The agent's price is charged per period
+    require!(
+        amount_per_period >= ctx.accounts.agent_profile.price_lamports,
+        AgentProtocolError::InsufficientPayment
+    );
+
+    let total_escrow = amount_per_period
+        .checked_mul(total_periods as u64)
+        .ok_or(AgentProtocolError::Overflow)?;
+
+    // Fund every period up front into the Subscription PDA (escrow)
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.client.to_account_info(),
+                to: ctx.accounts.subscription.to_account_info(),
+            },
+        ),
+        total_escrow,
+    )?;
+
+    let nonce = ctx.accounts.job_counter.take_nonce(
+        ctx.accounts.client.key(),
+        ctx.bumps.job_counter,
+    )?;
+
+    let clock = Clock::get()?;
+    let sub = &mut ctx.accounts.subscription;
+    sub.client = ctx.accounts.client.key();
+    sub.agent = ctx.accounts.agent_profile.agent_id;
+    sub.agent_profile = ctx.accounts.agent_profile.key();
+    sub.amount_per_period = amount_per_period;
+    sub.period_seconds = period_seconds;
+    sub.total_periods = total_periods;
+    sub.claimed_periods = 0;
+    sub.next_claim_at = clock.unix_timestamp
+        .checked_add(period_seconds)
+        .ok_or(AgentProtocolError::Overflow)?;
+    sub.paused_at = None;
+    sub.created_at = clock.unix_timestamp;
+    sub.nonce = nonce;
+    sub.bump = ctx.bumps.subscription;
+
+    emit!(SubscriptionCreated {
+        subscription: sub.key(),
+        client: sub.client,
+        agent: sub.agent,
+        amount_per_period,
+        period_seconds,
+        total_periods,
+    });
+
+    Ok(())
+}
@@ -1,17 +1,22 @@
 use anchor_lang::prelude::*;
-use crate::state::{AgentProfile, Job, JobStatus};
+use crate::state::{
+    validate_title_and_spec, AgentAccessList, AgentProfile, Job, JobCounter, JobExtension, JobSpec, JobStatus,
+    JobTeam,
+};
 use crate::error::AgentProtocolError;
 use crate::events::JobDelegated;
-use crate::constants::MAX_ACTIVE_CHILDREN;
+use crate::constants::{DEFAULT_SLASH_BPS, MAX_ACTIVE_CHILDREN};
+use crate::math::apply_bps;
 
 #[derive(Accounts)]
-#[instruction(description: String, delegation_amount: u64, timestamp_seed: i64)]
 pub struct DelegateTask<'info> {
+    /// The parent agent's owner or operator; becomes the child job's client.
+    /// A cancelled or refunded child returns its escrow to the parent job, so
+    /// only the child's rent ever comes back to this key.
     #[account(mut)]
     pub delegating_agent: Signer<'info>,
     #[account(
         mut,
-        constraint = parent_job.agent == delegating_agent.key() @ AgentProtocolError::Unauthorized,
         constraint = (
             parent_job.status == JobStatus::Pending ||
             parent_job.status == JobStatus::InProgress
@@ -19,9 +24,43 @@ pub struct DelegateTask<'info> {
     )]
     pub parent_job: Account<'info, Job>,
     #[account(
+        constraint = agent_profile.agent_id == parent_job.agent @ AgentProtocolError::Unauthorized,
+        constraint = agent_profile.can_operate(delegating_agent.key()) @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    /// Team roster — required when parent_job.team_size > 0
+    #[account(
+        seeds = [b"team", parent_job.key().as_ref()],
+        bump = team.bump
+    )]
+    pub team: Option<Account<'info, JobTeam>>,
+    /// Parent's stream schedule and delegation policy — required when
+    /// parent_job.has_extension
+    #[account(
+        seeds = [b"job_ext", parent_job.key().as_ref()],
+        bump = parent_extension.bump
+    )]
+    pub parent_extension: Option<Account<'info, JobExtension>>,
+    #[account(
+        mut,
         constraint = sub_agent_profile.is_active @ AgentProtocolError::AgentNotActive
     )]
     pub sub_agent_profile: Account<'info, AgentProfile>,
+    /// CHECK: Sub-agent's access list, checked against the delegating key
+    /// (the child job's client). Enforced only if initialized.
+    #[account(
+        seeds = [b"access", sub_agent_profile.key().as_ref()],
+        bump
+    )]
+    pub sub_agent_access_list: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = delegating_agent,
+        space = 8 + JobCounter::INIT_SPACE,
+        seeds = [b"job_counter", delegating_agent.key().as_ref()],
+        bump
+    )]
+    pub job_counter: Account<'info, JobCounter>,
     #[account(
         init,
         payer = delegating_agent,
@@ -29,8 +68,7 @@ pub struct DelegateTask<'info> {
         seeds = [
             b"job",
             delegating_agent.key().as_ref(),
-            sub_agent_profile.key().as_ref(),
-            &timestamp_seed.to_le_bytes()
+            &job_counter.next_nonce.to_le_bytes()
         ],
         bump
     )]
@@ -40,18 +78,47 @@ pub struct DelegateTask<'info> {
 
 pub fn handler(
     ctx: Context<DelegateTask>,
-    description: String,
+    title: String,
     delegation_amount: u64,
-    timestamp_seed: i64,
+    spec: Option<JobSpec>,
 ) -> Result<()> {
-    require!(!description.is_empty(), AgentProtocolError::EmptyDescription);
-    require!(description.len() <= 256, AgentProtocolError::DescriptionTooLong);
+    validate_title_and_spec(&title, spec.as_ref())?;
+    AgentAccessList::check(
+        &ctx.accounts.sub_agent_access_list,
+        &ctx.accounts.delegating_agent.key(),
+    )?;
 
     let parent = &mut ctx.accounts.parent_job;
+    let parent_extension = ctx.accounts.parent_extension.as_deref();
+    parent.check_extension(parent_extension)?;
+    // Splitting escrow would break the parent's vesting schedule
+    require!(
+        parent_extension.is_none_or(|extension| extension.stream.is_none()),
+        AgentProtocolError::StreamingJob
+    );
     require!(
         parent.active_children < MAX_ACTIVE_CHILDREN,
         AgentProtocolError::TooManyDelegations
     );
+    if let Some(policy) = parent_extension.and_then(|extension| extension.delegation_policy.as_ref()) {
+        policy.authorize(
+            &ctx.accounts.sub_agent_profile,
+            delegation_amount,
+            parent.original_escrow,
+            parent.delegated_total,
+        )?;
+    }
+
+    // Members are paid their share of the whole team escrow, so the lead can
+    // only subcontract out of its own share
+    if parent.team_size > 0 {
+        let team = ctx.accounts.team.as_ref().ok_or(AgentProtocolError::TeamMismatch)?;
+        let lead_share = apply_bps(parent.original_escrow, team.members[0].share_bps)?;
+        let delegated = parent.delegated_total
+            .checked_add(delegation_amount)
+            .ok_or(AgentProtocolError::Overflow)?;
+        require!(delegated <= lead_share, AgentProtocolError::InsufficientEscrow);
+    }
 
     // Checked subtraction prevents underflow
     parent.escrow_lamports = parent.escrow_lamports
@@ -69,6 +136,9 @@ pub fn handler(
     parent.active_children = parent.active_children
         .checked_add(1)
         .ok_or(AgentProtocolError::Overflow)?;
+    parent.delegated_total = parent.delegated_total
+        .checked_add(delegation_amount)
+        .ok_or(AgentProtocolError::Overflow)?;
 
     // Auto-flip parent to InProgress
     if parent.status == JobStatus::Pending {
@@ -80,28 +150,54 @@ pub fn handler(
     let child_info = ctx.accounts.child_job.to_account_info();
     **child_info.try_borrow_mut_lamports()? += delegation_amount;
 
+    ctx.accounts.sub_agent_profile.open_job()?;
+
     let clock = Clock::get()?;
+    let nonce = ctx.accounts.job_counter.take_nonce(
+        ctx.accounts.delegating_agent.key(),
+        ctx.bumps.job_counter,
+    )?;
+
     let child = &mut ctx.accounts.child_job;
     child.client = ctx.accounts.delegating_agent.key();
-    child.agent = ctx.accounts.sub_agent_profile.owner;
+    child.agent = ctx.accounts.sub_agent_profile.agent_id;
     child.escrow_lamports = delegation_amount;
     child.status = JobStatus::Pending;
-    child.description = description;
+    child.title = title;
+    child.spec = spec;
     child.result_uri = String::new();
+    child.result_hash = None;
     child.parent_job = Some(ctx.accounts.parent_job.key());
     child.active_children = 0;
     child.auto_release_at = None;
     child.disputed_at = None;
     child.created_at = clock.unix_timestamp;
     child.completed_at = None;
-    child.timestamp_seed = timestamp_seed;
+    child.nonce = nonce;
+    child.required_capabilities = 0;
+    child.min_rating_x100 = 0;
+    child.claim_deadline = None;
+    child.team_size = 0;
+    child.pending_signoffs = 0;
+    child.group_id = None;
+    child.bonus_lamports = 0;
+    child.partial_release_lamports = None;
+    child.original_escrow = delegation_amount;
+    child.delegated_total = 0;
+    child.child_results_hash = [0u8; 32];
+    child.children_completed = 0;
+    child.price_tier = 0;
+    child.disputed_by = None;
+    child.slash_bps = DEFAULT_SLASH_BPS;
+    child.auto_release_delay = None;
+    child.has_extension = false;
     child.bump = ctx.bumps.child_job;
 
     emit!(JobDelegated {
         parent_job: ctx.accounts.parent_job.key(),
         child_job: child.key(),
         delegating_agent: ctx.accounts.delegating_agent.key(),
-        sub_agent: ctx.accounts.sub_agent_profile.owner,
+        sub_agent: ctx.accounts.sub_agent_profile.agent_id,
         amount: delegation_amount,
     });
 
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use crate::state::AgentProfile;
+use crate::error::AgentProtocolError;
+
+#[derive(Accounts)]
+pub struct Heartbeat<'info> {
+    /// The agent's owner or operator
+    pub agent: Signer<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.can_operate(agent.key()) @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+}
+
+/// Mark the agent as online. Sent on a timer, so it only stamps
+/// `last_seen_at` and emits no event.
+pub fn handler(ctx: Context<Heartbeat>) -> Result<()> {
+    ctx.accounts.agent_profile.last_seen_at = Some(Clock::get()?.unix_timestamp);
+    Ok(())
+}
@@ -1,18 +1,30 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::{AgentProfile, Job, JobStatus};
+use crate::state::{
+    validate_title_and_spec, AgentAccessList, AgentProfile, AgentStake, InvokeOptions, Job, JobCounter,
+    JobExtension, JobStatus, StreamSchedule,
+};
+use crate::constants::DEFAULT_SLASH_BPS;
 use crate::error::AgentProtocolError;
 use crate::events::JobCreated;
 
 #[derive(Accounts)]
-#[instruction(description: String, payment_lamports: u64, auto_release_seconds: Option<i64>, timestamp_seed: i64)]
 pub struct InvokeAgent<'info> {
     #[account(mut)]
     pub client: Signer<'info>,
     #[account(
+        mut,
         constraint = agent_profile.is_active @ AgentProtocolError::AgentNotActive
     )]
     pub agent_profile: Account<'info, AgentProfile>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + JobCounter::INIT_SPACE,
+        seeds = [b"job_counter", client.key().as_ref()],
+        bump
+    )]
+    pub job_counter: Account<'info, JobCounter>,
     #[account(
         init,
         payer = client,
@@ -20,32 +32,111 @@ pub struct InvokeAgent<'info> {
         seeds = [
             b"job",
             client.key().as_ref(),
-            agent_profile.key().as_ref(),
-            &timestamp_seed.to_le_bytes()
+            &job_counter.next_nonce.to_le_bytes()
         ],
         bump
     )]
     pub job: Account<'info, Job>,
+    /// Extension PDA — required exactly when a stream, encryption key or
+    /// delegation policy is requested
+    #[account(
+        init,
+        payer = client,
+        space = 8 + JobExtension::INIT_SPACE,
+        seeds = [b"job_ext", job.key().as_ref()],
+        bump
+    )]
+    pub job_extension: Option<Account<'info, JobExtension>>,
+    /// Optional stake vault — required when stake_requirement is Some
+    #[account(
+        seeds = [b"stake", agent_profile.key().as_ref()],
+        bump = agent_stake.bump
+    )]
+    pub agent_stake: Option<Account<'info, AgentStake>>,
+    /// CHECK: Agent's access list, always derived from the profile so the
+    /// client cannot skip it. Enforced only if initialized.
+    #[account(
+        seeds = [b"access", agent_profile.key().as_ref()],
+        bump
+    )]
+    pub access_list: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(
     ctx: Context<InvokeAgent>,
-    description: String,
+    title: String,
     payment_lamports: u64,
-    auto_release_seconds: Option<i64>,
-    timestamp_seed: i64,
+    options: InvokeOptions,
 ) -> Result<()> {
-    require!(!description.is_empty(), AgentProtocolError::EmptyDescription);
-    // Belt-and-suspenders: runtime length check on top of #[max_len(256)]
-    require!(description.len() <= 256, AgentProtocolError::DescriptionTooLong);
+    let InvokeOptions {
+        auto_release_seconds,
+        stake_requirement,
+        stream,
+        client_encryption_key,
+        spec,
+        delegation_policy,
+        required_capabilities,
+        price_tier,
+        max_heartbeat_age,
+    } = options;
+    validate_title_and_spec(&title, spec.as_ref())?;
+    if let Some(policy) = &delegation_policy {
+        policy.validate()?;
+    }
+
+    AgentAccessList::check(&ctx.accounts.access_list, &ctx.accounts.client.key())?;
+
+    // Requested capabilities select the agent's price; none means its default price
+    let profile = &ctx.accounts.agent_profile;
+    require!(
+        profile.capabilities & required_capabilities == required_capabilities,
+        AgentProtocolError::CapabilityMismatch
+    );
     require!(
-        payment_lamports >= ctx.accounts.agent_profile.price_lamports,
+        payment_lamports >= profile.price_for(required_capabilities, price_tier),
         AgentProtocolError::InsufficientPayment
     );
 
+    // Client-side collateral requirement; an agent that never staked has zero
+    if let Some(requirement) = &stake_requirement {
+        requirement.validate()?;
+        let staked = ctx.accounts.agent_stake
+            .as_ref()
+            .map_or(0, |stake| stake.staked_lamports);
+        require!(staked >= requirement.min_lamports, AgentProtocolError::InsufficientStake);
+    }
+
+    // Encrypted delivery needs a key on both ends of the exchange
+    if let Some(key) = client_encryption_key {
+        require!(key != [0u8; 32], AgentProtocolError::InvalidEncryptionKey);
+        require!(
+            ctx.accounts.agent_profile.encryption_key.is_some(),
+            AgentProtocolError::EncryptionKeyMissing
+        );
+    }
+
+    ctx.accounts.agent_profile.open_job()?;
+
     let clock = Clock::get()?;
 
+    // Automated clients can refuse to escrow with an agent that went quiet
+    if let Some(max_age) = max_heartbeat_age {
+        require!(
+            ctx.accounts.agent_profile.is_live(clock.unix_timestamp, max_age),
+            AgentProtocolError::AgentStale
+        );
+    }
+
+    // Streaming jobs pay out by time, so an auto-release timer makes no sense
+    if let Some(window) = stream {
+        require!(
+            window.end_at > window.start_at && window.end_at > clock.unix_timestamp,
+            AgentProtocolError::InvalidStreamWindow
+        );
+        require!(auto_release_seconds.is_none(), AgentProtocolError::StreamingJob);
+    }
+
     // Transfer SOL from client to Job PDA (escrow)
     system_program::transfer(
         CpiContext::new(
@@ -58,28 +149,73 @@ pub fn handler(
         payment_lamports,
     )?;
 
+    let has_extension = stream.is_some()
+        || client_encryption_key.is_some()
+        || delegation_policy.is_some();
+    require!(
+        has_extension == ctx.accounts.job_extension.is_some(),
+        AgentProtocolError::ExtensionMismatch
+    );
+    if let Some(ext) = ctx.accounts.job_extension.as_mut() {
+        ext.job = ctx.accounts.job.key();
+        ext.stream = stream.map(|window| StreamSchedule {
+            start_at: window.start_at,
+            end_at: window.end_at,
+            total_lamports: payment_lamports,
+            withdrawn_lamports: 0,
+        });
+        ext.client_encryption_key = client_encryption_key;
+        ext.result_envelope = None;
+        ext.delegation_policy = delegation_policy;
+        ext.bump = ctx.bumps.job_extension.ok_or(AgentProtocolError::ExtensionMismatch)?;
+    }
+
     let auto_release_at = auto_release_seconds.map(|s| clock.unix_timestamp + s);
 
+    let nonce = ctx.accounts.job_counter.take_nonce(
+        ctx.accounts.client.key(),
+        ctx.bumps.job_counter,
+    )?;
+
     let job = &mut ctx.accounts.job;
     job.client = ctx.accounts.client.key();
-    job.agent = ctx.accounts.agent_profile.owner;
+    job.agent = ctx.accounts.agent_profile.agent_id;
     job.escrow_lamports = payment_lamports;
     job.status = JobStatus::Pending;
-    job.description = description;
+    job.title = title;
+    job.spec = spec;
     job.result_uri = String::new();
+    job.result_hash = None;
     job.parent_job = None;
     job.active_children = 0;
     job.auto_release_at = auto_release_at;
     job.disputed_at = None;
     job.created_at = clock.unix_timestamp;
     job.completed_at = None;
-    job.timestamp_seed = timestamp_seed;
+    job.nonce = nonce;
+    job.required_capabilities = required_capabilities;
+    job.min_rating_x100 = 0;
+    job.claim_deadline = None;
+    job.team_size = 0;
+    job.pending_signoffs = 0;
+    job.group_id = None;
+    job.bonus_lamports = 0;
+    job.partial_release_lamports = None;
+    job.original_escrow = payment_lamports;
+    job.delegated_total = 0;
+    job.child_results_hash = [0u8; 32];
+    job.children_completed = 0;
+    job.price_tier = price_tier;
+    job.disputed_by = None;
+    job.slash_bps = stake_requirement.map_or(DEFAULT_SLASH_BPS, |requirement| requirement.slash_bps);
+    job.auto_release_delay = None;
+    job.has_extension = has_extension;
     job.bump = ctx.bumps.job;
 
     emit!(JobCreated {
         job: job.key(),
         client: ctx.accounts.client.key(),
-        agent: ctx.accounts.agent_profile.owner,
+        agent: ctx.accounts.agent_profile.agent_id,
         escrow_lamports: payment_lamports,
         auto_release_at,
     });
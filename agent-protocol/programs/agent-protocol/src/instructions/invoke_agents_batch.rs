@@ -0,0 +1,211 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{
+    validate_title_and_spec, AgentAccessList, AgentProfile, BatchEntry, Job, JobCounter, JobStatus,
+};
+use crate::error::AgentProtocolError;
+use crate::events::{JobBatchCreated, JobCreated};
+use crate::constants::{DEFAULT_SLASH_BPS, MAX_BATCH_JOBS};
+
+#[derive(Accounts)]
+pub struct InvokeAgentsBatch<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + JobCounter::INIT_SPACE,
+        seeds = [b"job_counter", client.key().as_ref()],
+        bump
+    )]
+    pub job_counter: Account<'info, JobCounter>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Remaining accounts come in `[agent_profile, access_list, job]` triples, one
+/// per entry; the profile and job are writable (each job takes a slot of its
+/// agent's capacity) and the access list is the profile's `["access",
+/// agent_profile]` PDA, checked against the client. Job
+/// PDAs take consecutive nonces from the client's counter, so the caller derives
+/// them as `["job", client, next_nonce + i]`. Every job records the first job's
+/// address as its group ID.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, InvokeAgentsBatch<'info>>,
+    entries: Vec<BatchEntry>,
+    auto_release_seconds: Option<i64>,
+) -> Result<()> {
+    require!(
+        (1..=MAX_BATCH_JOBS).contains(&entries.len())
+            && ctx.remaining_accounts.len() == entries.len() * 3,
+        AgentProtocolError::InvalidBatch
+    );
+
+    let client = ctx.accounts.client.key();
+    let clock = Clock::get()?;
+    let auto_release_at = auto_release_seconds.map(|s| clock.unix_timestamp + s);
+    let rent = Rent::get()?.minimum_balance(8 + Job::INIT_SPACE);
+    let group_id = ctx.remaining_accounts[2].key();
+
+    let mut jobs = Vec::with_capacity(entries.len());
+    let mut total_escrow: u64 = 0;
+    for (entry, accounts) in entries.into_iter().zip(ctx.remaining_accounts.chunks(3)) {
+        let (profile_info, access_info, job_info) = (&accounts[0], &accounts[1], &accounts[2]);
+        validate_title_and_spec(&entry.title, entry.spec.as_ref())?;
+
+        let mut profile = Account::<AgentProfile>::try_from(profile_info)?;
+        require!(profile.is_active, AgentProtocolError::AgentNotActive);
+        AgentAccessList::check_for(&profile.key(), access_info, &client)?;
+        require!(
+            entry.payment_lamports >= profile.price_lamports,
+            AgentProtocolError::InsufficientPayment
+        );
+        // Written back right away so a repeated profile sees its own count
+        profile.open_job()?;
+        profile.exit(&crate::ID)?;
+
+        let nonce = ctx.accounts.job_counter.take_nonce(client, ctx.bumps.job_counter)?;
+        let nonce_bytes = nonce.to_le_bytes();
+        let (expected, bump) = Pubkey::find_program_address(
+            &[b"job", client.as_ref(), &nonce_bytes],
+            ctx.program_id,
+        );
+        require_keys_eq!(job_info.key(), expected, AgentProtocolError::InvalidBatch);
+
+        create_job_account(
+            &ctx.accounts.client.to_account_info(),
+            job_info,
+            &ctx.accounts.system_program.to_account_info(),
+            &[b"job", client.as_ref(), &nonce_bytes, &[bump]],
+            rent,
+            entry.payment_lamports,
+        )?;
+
+        let job = Job {
+            client,
+            agent: profile.agent_id,
+            escrow_lamports: entry.payment_lamports,
+            status: JobStatus::Pending,
+            title: entry.title,
+            spec: entry.spec,
+            result_uri: String::new(),
+            result_hash: None,
+            parent_job: None,
+            active_children: 0,
+            auto_release_at,
+            disputed_at: None,
+            created_at: clock.unix_timestamp,
+            completed_at: None,
+            nonce,
+            required_capabilities: 0,
+            min_rating_x100: 0,
+            claim_deadline: None,
+            team_size: 0,
+            pending_signoffs: 0,
+            group_id: Some(group_id),
+            bonus_lamports: 0,
+            partial_release_lamports: None,
+            original_escrow: entry.payment_lamports,
+            delegated_total: 0,
+            child_results_hash: [0u8; 32],
+            children_completed: 0,
+            price_tier: 0,
+            disputed_by: None,
+            slash_bps: DEFAULT_SLASH_BPS,
+            auto_release_delay: None,
+            has_extension: false,
+            bump,
+        };
+        job.try_serialize(&mut &mut job_info.try_borrow_mut_data()?[..])?;
+
+        emit!(JobCreated {
+            job: job_info.key(),
+            client,
+            agent: profile.agent_id,
+            escrow_lamports: entry.payment_lamports,
+            auto_release_at,
+        });
+
+        total_escrow = total_escrow
+            .checked_add(entry.payment_lamports)
+            .ok_or(AgentProtocolError::Overflow)?;
+        jobs.push(job_info.key());
+    }
+
+    emit!(JobBatchCreated {
+        group_id,
+        client,
+        jobs,
+        total_escrow_lamports: total_escrow,
+    });
+
+    Ok(())
+}
+
+/// Create a Job PDA holding rent + escrow. Like Anchor's `init`, a PDA that
+/// someone pre-funded is topped up, allocated and assigned instead of failing
+/// in `create_account`.
+fn create_job_account<'info>(
+    client: &AccountInfo<'info>,
+    job_info: &AccountInfo<'info>,
+    system_program_info: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    rent: u64,
+    escrow: u64,
+) -> Result<()> {
+    let space = 8 + Job::INIT_SPACE;
+    let current = job_info.lamports();
+
+    if current == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program_info.clone(),
+                system_program::CreateAccount {
+                    from: client.clone(),
+                    to: job_info.clone(),
+                },
+                &[signer_seeds],
+            ),
+            rent.checked_add(escrow).ok_or(AgentProtocolError::Overflow)?,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    // Stray lamports only count toward rent; the escrow always comes from the client
+    let top_up = rent
+        .saturating_sub(current)
+        .checked_add(escrow)
+        .ok_or(AgentProtocolError::Overflow)?;
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program_info.clone(),
+                system_program::Transfer {
+                    from: client.clone(),
+                    to: job_info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program_info.clone(),
+            system_program::Allocate {
+                account_to_allocate: job_info.clone(),
+            },
+            &[signer_seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program_info.clone(),
+            system_program::Assign {
+                account_to_assign: job_info.clone(),
+            },
+            &[signer_seeds],
+        ),
+        &crate::ID,
+    )
+}
@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{
+    validate_title_and_spec, AgentAccessList, AgentProfile, Job, JobCounter, JobSpec, JobStatus, JobTeam,
+    TeamMember,
+};
+use crate::error::AgentProtocolError;
+use crate::events::{JobCreated, TeamJobCreated};
+use crate::constants::{BPS_DENOMINATOR, DEFAULT_SLASH_BPS, MAX_TEAM_MEMBERS};
+use crate::math::apply_bps;
+
+#[derive(Accounts)]
+pub struct InvokeTeam<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        constraint = lead_profile.is_active @ AgentProtocolError::AgentNotActive
+    )]
+    pub lead_profile: Account<'info, AgentProfile>,
+    /// CHECK: Lead's access list, always derived from the profile. Enforced
+    /// only if initialized.
+    #[account(
+        seeds = [b"access", lead_profile.key().as_ref()],
+        bump
+    )]
+    pub lead_access_list: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + JobCounter::INIT_SPACE,
+        seeds = [b"job_counter", client.key().as_ref()],
+        bump
+    )]
+    pub job_counter: Account<'info, JobCounter>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Job::INIT_SPACE,
+        seeds = [
+            b"job",
+            client.key().as_ref(),
+            &job_counter.next_nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + JobTeam::INIT_SPACE,
+        seeds = [b"team", job.key().as_ref()],
+        bump
+    )]
+    pub team: Account<'info, JobTeam>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The other members are passed as `[agent_profile, access_list]` pairs in the
+/// remaining accounts, in the same order as `shares_bps[1..]`;
+/// `shares_bps[0]` is the lead's share. Each access list is the member's
+/// `["access", agent_profile]` PDA and is checked against the client.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, InvokeTeam<'info>>,
+    title: String,
+    payment_lamports: u64,
+    auto_release_seconds: Option<i64>,
+    shares_bps: Vec<u16>,
+    require_all_signoff: bool,
+    spec: Option<JobSpec>,
+) -> Result<()> {
+    validate_title_and_spec(&title, spec.as_ref())?;
+
+    let team_size = shares_bps.len();
+    require!(
+        (2..=MAX_TEAM_MEMBERS).contains(&team_size)
+            && ctx.remaining_accounts.len() == (team_size - 1) * 2,
+        AgentProtocolError::InvalidTeamSize
+    );
+    require!(
+        shares_bps.iter().all(|&bps| bps > 0)
+            && shares_bps.iter().map(|&bps| bps as u32).sum::<u32>() == BPS_DENOMINATOR as u32,
+        AgentProtocolError::InvalidTeamShares
+    );
+
+    // Only the lead is the job's agent, so only its capacity is taken
+    ctx.accounts.lead_profile.open_job()?;
+
+    // Each member's share must cover its listed price, just like a solo invoke
+    let client = ctx.accounts.client.key();
+    let lead = &ctx.accounts.lead_profile;
+    AgentAccessList::check(&ctx.accounts.lead_access_list, &client)?;
+    require!(
+        apply_bps(payment_lamports, shares_bps[0])? >= lead.price_lamports,
+        AgentProtocolError::InsufficientPayment
+    );
+    let mut members = vec![TeamMember {
+        agent_profile: lead.key(),
+        agent: lead.agent_id,
+        share_bps: shares_bps[0],
+        signed_off: false,
+    }];
+    for (pair, &share_bps) in ctx.remaining_accounts.chunks(2).zip(&shares_bps[1..]) {
+        let profile = Account::<AgentProfile>::try_from(&pair[0])?;
+        require!(profile.is_active, AgentProtocolError::AgentNotActive);
+        AgentAccessList::check_for(&profile.key(), &pair[1], &client)?;
+        require!(
+            members.iter().all(|m| m.agent_profile != profile.key()),
+            AgentProtocolError::DuplicateTeamMember
+        );
+        require!(
+            apply_bps(payment_lamports, share_bps)? >= profile.price_lamports,
+            AgentProtocolError::InsufficientPayment
+        );
+        members.push(TeamMember {
+            agent_profile: profile.key(),
+            agent: profile.agent_id,
+            share_bps,
+            signed_off: false,
+        });
+    }
+
+    // Transfer SOL from client to Job PDA (escrow)
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.client.to_account_info(),
+                to: ctx.accounts.job.to_account_info(),
+            },
+        ),
+        payment_lamports,
+    )?;
+
+    let clock = Clock::get()?;
+    let auto_release_at = auto_release_seconds.map(|s| clock.unix_timestamp + s);
+    let nonce = ctx.accounts.job_counter.take_nonce(
+        ctx.accounts.client.key(),
+        ctx.bumps.job_counter,
+    )?;
+
+    let job = &mut ctx.accounts.job;
+    job.client = ctx.accounts.client.key();
+    job.agent = lead.agent_id;
+    job.escrow_lamports = payment_lamports;
+    job.status = JobStatus::Pending;
+    job.title = title;
+    job.spec = spec;
+    job.result_uri = String::new();
+    job.result_hash = None;
+    job.parent_job = None;
+    job.active_children = 0;
+    job.auto_release_at = auto_release_at;
+    job.disputed_at = None;
+    job.created_at = clock.unix_timestamp;
+    job.completed_at = None;
+    job.nonce = nonce;
+    job.required_capabilities = 0;
+    job.min_rating_x100 = 0;
+    job.claim_deadline = None;
+    job.team_size = team_size as u8;
+    // The lead's delivery counts as its own sign-off
+    job.pending_signoffs = if require_all_signoff { team_size as u8 - 1 } else { 0 };
+    job.group_id = None;
+    job.bonus_lamports = 0;
+    job.partial_release_lamports = None;
+    job.original_escrow = payment_lamports;
+    job.delegated_total = 0;
+    job.child_results_hash = [0u8; 32];
+    job.children_completed = 0;
+    job.price_tier = 0;
+    job.disputed_by = None;
+    job.slash_bps = DEFAULT_SLASH_BPS;
+    job.auto_release_delay = None;
+    job.has_extension = false;
+    job.bump = ctx.bumps.job;
+
+    let team = &mut ctx.accounts.team;
+    team.job = job.key();
+    team.members = members;
+    team.require_all_signoff = require_all_signoff;
+    team.bump = ctx.bumps.team;
+
+    emit!(JobCreated {
+        job: job.key(),
+        client: ctx.accounts.client.key(),
+        agent: lead.agent_id,
+        escrow_lamports: payment_lamports,
+        auto_release_at,
+    });
+    emit!(TeamJobCreated {
+        job: job.key(),
+        client: ctx.accounts.client.key(),
+        members: team.members.iter().map(|m| m.agent_profile).collect(),
+        shares_bps,
+        escrow_lamports: payment_lamports,
+        require_all_signoff,
+    });
+
+    Ok(())
+}
@@ -1,6 +1,10 @@
+// Every instruction module exports a `handler`; lib.rs always calls them fully qualified
+#![allow(ambiguous_glob_reexports)]
+
 pub mod register_agent;
 pub mod invoke_agent;
 pub mod update_job;
+pub mod commit_result;
 pub mod release_payment;
 pub mod auto_release;
 pub mod cancel_job;
@@ -8,10 +12,45 @@ pub mod delegate_task;
 pub mod raise_dispute;
 pub mod resolve_dispute;
 pub mod rate_agent;
+pub mod stake_agent;
+pub mod request_unstake;
+pub mod withdraw_stake;
+pub mod post_bounty;
+pub mod claim_bounty;
+pub mod create_job_request;
+pub mod submit_bid;
+pub mod reveal_bid;
+pub mod accept_bid;
+pub mod cancel_job_request;
+pub mod close_bid;
+pub mod create_subscription;
+pub mod claim_period;
+pub mod pause_subscription;
+pub mod resume_subscription;
+pub mod cancel_subscription;
+pub mod withdraw_stream;
+pub mod stop_stream;
+pub mod set_encryption_key;
+pub mod invoke_team;
+pub mod sign_off_result;
+pub mod invoke_agents_batch;
+pub mod propose_partial_release;
+pub mod accept_partial_release;
+pub mod reassign_job;
+pub mod cancel_job_tree;
+pub mod set_max_concurrent_jobs;
+pub mod set_price_table;
+pub mod set_agent_keys;
+pub mod propose_agent_transfer;
+pub mod accept_agent_transfer;
+pub mod set_agent_metadata;
+pub mod heartbeat;
+pub mod set_access_list;
 
 pub use register_agent::*;
 pub use invoke_agent::*;
 pub use update_job::*;
+pub use commit_result::*;
 pub use release_payment::*;
 pub use auto_release::*;
 pub use cancel_job::*;
@@ -19,3 +58,37 @@ pub use delegate_task::*;
 pub use raise_dispute::*;
 pub use resolve_dispute::*;
 pub use rate_agent::*;
+pub use stake_agent::*;
+pub use request_unstake::*;
+pub use withdraw_stake::*;
+pub use post_bounty::*;
+pub use claim_bounty::*;
+pub use create_job_request::*;
+pub use submit_bid::*;
+pub use reveal_bid::*;
+pub use accept_bid::*;
+pub use cancel_job_request::*;
+pub use close_bid::*;
+pub use create_subscription::*;
+pub use claim_period::*;
+pub use pause_subscription::*;
+pub use resume_subscription::*;
+pub use cancel_subscription::*;
+pub use withdraw_stream::*;
+pub use stop_stream::*;
+pub use set_encryption_key::*;
+pub use invoke_team::*;
+pub use sign_off_result::*;
+pub use invoke_agents_batch::*;
+pub use propose_partial_release::*;
+pub use accept_partial_release::*;
+pub use reassign_job::*;
+pub use cancel_job_tree::*;
+pub use set_max_concurrent_jobs::*;
+pub use set_price_table::*;
+pub use set_agent_keys::*;
+pub use propose_agent_transfer::*;
+pub use accept_agent_transfer::*;
+pub use set_agent_metadata::*;
+pub use heartbeat::*;
+pub use set_access_list::*;
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::Subscription;
+use crate::error::AgentProtocolError;
+use crate::events::SubscriptionPaused;
+
+#[derive(Accounts)]
+pub struct PauseSubscription<'info> {
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        constraint = subscription.client == client.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub subscription: Account<'info, Subscription>,
+}
+
+pub fn handler(ctx: Context<PauseSubscription>) -> Result<()> {
+    let sub = &mut ctx.accounts.subscription;
+    require!(sub.paused_at.is_none(), AgentProtocolError::SubscriptionPaused);
+    require!(
+        sub.claimed_periods < sub.total_periods,
+        AgentProtocolError::SubscriptionComplete
+    );
+
+    sub.paused_at = Some(Clock::get()?.unix_timestamp);
+
+    emit!(SubscriptionPaused {
+        subscription: sub.key(),
+        client: ctx.accounts.client.key(),
+    });
+
+    Ok(())
+}
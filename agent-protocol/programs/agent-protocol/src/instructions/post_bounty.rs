@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{validate_title_and_spec, Job, JobCounter, JobSpec, JobStatus};
+use crate::constants::DEFAULT_SLASH_BPS;
+use crate::error::AgentProtocolError;
+use crate::events::BountyPosted;
+
+#[derive(Accounts)]
+pub struct PostBounty<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + JobCounter::INIT_SPACE,
+        seeds = [b"job_counter", client.key().as_ref()],
+        bump
+    )]
+    pub job_counter: Account<'info, JobCounter>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Job::INIT_SPACE,
+        seeds = [
+            b"job",
+            client.key().as_ref(),
+            &job_counter.next_nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub job: Account<'info, Job>,
+    pub system_program: Program<'info, System>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<PostBounty>,
+    title: String,
+    payment_lamports: u64,
+    required_capabilities: u16,
+    min_rating_x100: u16,
+    claim_window_seconds: Option<i64>,
+    auto_release_seconds: Option<i64>,
+    spec: Option<JobSpec>,
+) -> Result<()> {
+    validate_title_and_spec(&title, spec.as_ref())?;
+    require!(payment_lamports > 0, AgentProtocolError::InsufficientPayment);
+    // Ratings are 1-5, so a minimum above 5.00 could never be met
+    require!(min_rating_x100 <= 500, AgentProtocolError::InvalidRating);
+
+    let clock = Clock::get()?;
+
+    // Transfer SOL from client to Job PDA (escrow)
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.client.to_account_info(),
+                to: ctx.accounts.job.to_account_info(),
+            },
+        ),
+        payment_lamports,
+    )?;
+
+    let claim_deadline = claim_window_seconds.map(|s| clock.unix_timestamp + s);
+
+    let nonce = ctx.accounts.job_counter.take_nonce(
+        ctx.accounts.client.key(),
+        ctx.bumps.job_counter,
+    )?;
+
+    let job = &mut ctx.accounts.job;
+    job.client = ctx.accounts.client.key();
+    // No agent until the bounty is claimed
+    job.agent = Pubkey::default();
+    job.escrow_lamports = payment_lamports;
+    job.status = JobStatus::Open;
+    job.title = title;
+    job.spec = spec;
+    job.result_uri = String::new();
+    job.result_hash = None;
+    job.parent_job = None;
+    job.active_children = 0;
+    // The timer starts at claim_bounty, so a late claimant still gets the full window
+    job.auto_release_at = None;
+    job.disputed_at = None;
+    job.created_at = clock.unix_timestamp;
+    job.completed_at = None;
+    job.nonce = nonce;
+    job.required_capabilities = required_capabilities;
+    job.min_rating_x100 = min_rating_x100;
+    job.claim_deadline = claim_deadline;
+    job.team_size = 0;
+    job.pending_signoffs = 0;
+    job.group_id = None;
+    job.bonus_lamports = 0;
+    job.partial_release_lamports = None;
+    job.original_escrow = payment_lamports;
+    job.delegated_total = 0;
+    job.child_results_hash = [0u8; 32];
+    job.children_completed = 0;
+    job.price_tier = 0;
+    job.disputed_by = None;
+    job.slash_bps = DEFAULT_SLASH_BPS;
+    job.auto_release_delay = auto_release_seconds;
+    job.has_extension = false;
+    job.bump = ctx.bumps.job;
+
+    emit!(BountyPosted {
+        job: job.key(),
+        client: ctx.accounts.client.key(),
+        escrow_lamports: payment_lamports,
+        required_capabilities,
+        min_rating_x100,
+        claim_deadline,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::state::AgentProfile;
+use crate::error::AgentProtocolError;
+use crate::events::AgentTransferProposed;
+
+#[derive(Accounts)]
+pub struct ProposeAgentTransfer<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.owner == owner.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+}
+
+/// First step of an ownership transfer: name the key that may take the profile
+/// over with accept_agent_transfer. `None` withdraws a pending proposal.
+pub fn handler(ctx: Context<ProposeAgentTransfer>, new_owner: Option<Pubkey>) -> Result<()> {
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.pending_owner = new_owner;
+
+    emit!(AgentTransferProposed {
+        agent: profile.key(),
+        owner: ctx.accounts.owner.key(),
+        pending_owner: new_owner,
+    });
+
+    Ok(())
+}
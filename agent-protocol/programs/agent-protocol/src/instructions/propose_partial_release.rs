@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::state::{Job, JobExtension, JobStatus};
+use crate::error::AgentProtocolError;
+use crate::events::PartialReleaseProposed;
+
+#[derive(Accounts)]
+pub struct ProposePartialRelease<'info> {
+    #[account(
+        constraint = job.client == client.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub client: Signer<'info>,
+    #[account(mut)]
+    pub job: Account<'info, Job>,
+    /// Stream schedule — required when job.has_extension
+    #[account(
+        seeds = [b"job_ext", job.key().as_ref()],
+        bump = job_extension.bump
+    )]
+    pub job_extension: Option<Account<'info, JobExtension>>,
+}
+
+/// Offer the agent `agent_lamports` of the escrow and refund the rest. A new
+/// proposal replaces the previous one; nothing moves until the agent accepts.
+pub fn handler(ctx: Context<ProposePartialRelease>, agent_lamports: u64) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+    require!(
+        job.status == JobStatus::InProgress || job.status == JobStatus::Completed,
+        AgentProtocolError::InvalidJobStatus
+    );
+    // Streams settle pro rata through stop_stream instead
+    job.check_extension(ctx.accounts.job_extension.as_deref())?;
+    require!(
+        ctx.accounts.job_extension.as_ref().is_none_or(|extension| extension.stream.is_none()),
+        AgentProtocolError::StreamingJob
+    );
+    require!(job.active_children == 0, AgentProtocolError::UnresolvedChildren);
+    require!(agent_lamports <= job.escrow_lamports, AgentProtocolError::InsufficientEscrow);
+
+    job.partial_release_lamports = Some(agent_lamports);
+
+    emit!(PartialReleaseProposed {
+        job: job.key(),
+        client: job.client,
+        agent_lamports,
+        refund_lamports: job.escrow_lamports - agent_lamports,
+    });
+
+    Ok(())
+}
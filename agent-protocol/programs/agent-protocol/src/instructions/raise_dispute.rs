@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::{Job, JobStatus};
+use crate::state::{AgentProfile, Job, JobStatus};
 use crate::error::AgentProtocolError;
 use crate::events::DisputeRaised;
 
@@ -8,15 +8,22 @@ pub struct RaiseDispute<'info> {
     pub disputant: Signer<'info>,
     #[account(mut)]
     pub job: Account<'info, Job>,
+    /// Assigned agent's profile; counts client disputes against its stake
+    #[account(
+        mut,
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
 }
 
 pub fn handler(ctx: Context<RaiseDispute>) -> Result<()> {
     let job = &mut ctx.accounts.job;
     let disputant = ctx.accounts.disputant.key();
 
-    // Must be client or agent
+    // Must be client or agent (owner or operator)
+    let is_client = disputant == job.client;
     require!(
-        disputant == job.client || disputant == job.agent,
+        is_client || ctx.accounts.agent_profile.can_operate(disputant),
         AgentProtocolError::Unauthorized
     );
 
@@ -30,6 +37,15 @@ pub fn handler(ctx: Context<RaiseDispute>) -> Result<()> {
 
     job.status = JobStatus::Disputed;
     job.disputed_at = Some(Clock::get()?.unix_timestamp);
+    job.disputed_by = Some(disputant);
+
+    // Only client disputes can slash, so only they hold the stake in place
+    if is_client {
+        let profile = &mut ctx.accounts.agent_profile;
+        profile.open_disputes = profile.open_disputes
+            .checked_add(1)
+            .ok_or(AgentProtocolError::Overflow)?;
+    }
 
     emit!(DisputeRaised {
         job: job.key(),
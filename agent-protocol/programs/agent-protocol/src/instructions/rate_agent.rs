@@ -12,7 +12,10 @@ pub struct RateAgent<'info> {
         constraint = job.client == client.key() @ AgentProtocolError::Unauthorized
     )]
     pub job: Account<'info, Job>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized
+    )]
     pub agent_profile: Account<'info, AgentProfile>,
     #[account(
         init,
@@ -26,7 +29,7 @@ pub struct RateAgent<'info> {
 }
 
 pub fn handler(ctx: Context<RateAgent>, score: u8) -> Result<()> {
-    require!(score >= 1 && score <= 5, AgentProtocolError::InvalidRating);
+    require!((1..=5).contains(&score), AgentProtocolError::InvalidRating);
 
     let rating = &mut ctx.accounts.rating;
     rating.agent = ctx.accounts.agent_profile.key();
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    AgentAccessList, AgentProfile, AgentStake, Job, JobExtension, JobStatus,
+    StakeRequirement,
+};
+use crate::constants::DEFAULT_SLASH_BPS;
+use crate::error::AgentProtocolError;
+use crate::events::JobReassigned;
+
+#[derive(Accounts)]
+pub struct ReassignJob<'info> {
+    #[account(
+        constraint = job.client == client.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub client: Signer<'info>,
+    #[account(mut)]
+    pub job: Account<'info, Job>,
+    /// Stream schedule and client encryption key — required when job.has_extension
+    #[account(
+        seeds = [b"job_ext", job.key().as_ref()],
+        bump = job_extension.bump
+    )]
+    pub job_extension: Option<Account<'info, JobExtension>>,
+    /// Current agent's profile; its concurrency slot moves to the new agent
+    #[account(
+        mut,
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    #[account(
+        mut,
+        constraint = new_agent_profile.is_active @ AgentProtocolError::AgentNotActive
+    )]
+    pub new_agent_profile: Account<'info, AgentProfile>,
+    /// CHECK: New agent's access list. Enforced only if initialized.
+    #[account(
+        seeds = [b"access", new_agent_profile.key().as_ref()],
+        bump
+    )]
+    pub new_agent_access_list: UncheckedAccount<'info>,
+    /// New agent's stake vault — required when stake_requirement is Some
+    #[account(
+        seeds = [b"stake", new_agent_profile.key().as_ref()],
+        bump = new_agent_stake.bump
+    )]
+    pub new_agent_stake: Option<Account<'info, AgentStake>>,
+}
+
+/// Hand an undelivered job to another agent, keeping its escrow, nonce and
+/// history. The new agent must satisfy the same price floor, capability mask
+/// and rating a claimant of the job would, plus the client's stake
+/// requirement for the new agent, as in invoke_agent.
+pub fn handler(ctx: Context<ReassignJob>, stake_requirement: Option<StakeRequirement>) -> Result<()> {
+    require_keys_neq!(
+        ctx.accounts.new_agent_profile.key(),
+        ctx.accounts.agent_profile.key(),
+        AgentProtocolError::ReassignToSameAgent
+    );
+    AgentAccessList::check(&ctx.accounts.new_agent_access_list, &ctx.accounts.client.key())?;
+    let profile = &mut ctx.accounts.new_agent_profile;
+    let job = &mut ctx.accounts.job;
+    let extension = ctx.accounts.job_extension.as_deref();
+    job.check_extension(extension)?;
+
+    require!(
+        job.status == JobStatus::Pending || job.status == JobStatus::InProgress,
+        AgentProtocolError::InvalidJobStatus
+    );
+    // Nothing delivered or committed yet
+    require!(
+        job.result_uri.is_empty() && job.result_hash.is_none(),
+        AgentProtocolError::ResultAlreadyCommitted
+    );
+    require!(job.active_children == 0, AgentProtocolError::UnresolvedChildren);
+    // Team rosters are fixed at invocation
+    require!(job.team_size == 0, AgentProtocolError::InvalidJobStatus);
+    // Once a stream starts, vested lamports belong to the current agent
+    if let Some(stream) = extension.and_then(|extension| extension.stream) {
+        require!(
+            Clock::get()?.unix_timestamp < stream.start_at,
+            AgentProtocolError::StreamingJob
+        );
+    }
+
+    require!(
+        job.escrow_lamports >= profile.price_for(job.required_capabilities, job.price_tier),
+        AgentProtocolError::InsufficientPayment
+    );
+    require!(
+        profile.capabilities & job.required_capabilities == job.required_capabilities,
+        AgentProtocolError::CapabilityMismatch
+    );
+    require!(
+        profile.rating_avg_x100() >= job.min_rating_x100 as u64,
+        AgentProtocolError::RatingTooLow
+    );
+    // The slash share agreed with the previous agent does not carry over
+    if let Some(requirement) = &stake_requirement {
+        requirement.validate()?;
+        let staked = ctx.accounts.new_agent_stake
+            .as_ref()
+            .map_or(0, |stake| stake.staked_lamports);
+        require!(staked >= requirement.min_lamports, AgentProtocolError::InsufficientStake);
+    }
+    // Encrypted delivery needs a key the new agent can unwrap with
+    if extension.is_some_and(|extension| extension.client_encryption_key.is_some()) {
+        require!(
+            profile.encryption_key.is_some(),
+            AgentProtocolError::EncryptionKeyMissing
+        );
+    }
+
+    ctx.accounts.agent_profile.close_job()?;
+    profile.open_job()?;
+
+    let previous_agent = job.agent;
+    job.agent = profile.agent_id;
+    job.status = JobStatus::Pending;
+    // A split offered to the previous agent does not carry over
+    job.partial_release_lamports = None;
+    job.slash_bps = stake_requirement.map_or(DEFAULT_SLASH_BPS, |requirement| requirement.slash_bps);
+
+    emit!(JobReassigned {
+        job: job.key(),
+        client: job.client,
+        previous_agent,
+        new_agent: profile.agent_id,
+        new_agent_profile: profile.key(),
+    });
+
+    Ok(())
+}
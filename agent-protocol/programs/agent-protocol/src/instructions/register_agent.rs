@@ -41,6 +41,17 @@ pub fn handler(
     profile.rating_count = 0;
     profile.jobs_completed = 0;
     profile.created_at = Clock::get()?.unix_timestamp;
+    profile.encryption_key = None;
+    profile.lifetime_earnings = 0;
+    profile.max_concurrent_jobs = 0;
+    profile.active_jobs = 0;
+    profile.price_table = Vec::new();
+    profile.payout = ctx.accounts.owner.key();
+    profile.operator = None;
+    profile.agent_id = ctx.accounts.owner.key();
+    profile.pending_owner = None;
+    profile.last_seen_at = Some(profile.created_at);
+    profile.open_disputes = 0;
     profile.bump = ctx.bumps.agent_profile;
 
     emit!(AgentRegistered {
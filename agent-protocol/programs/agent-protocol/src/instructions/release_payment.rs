@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
-use crate::state::{AgentProfile, Job, JobStatus};
+use anchor_lang::system_program;
+use crate::state::{AgentProfile, Job, JobStatus, JobTeam};
 use crate::error::AgentProtocolError;
-use crate::events::PaymentReleased;
+use crate::events::{ChildResultRecorded, PaymentReleased};
+use crate::math::apply_bps;
 
 #[derive(Accounts)]
 pub struct ReleasePayment<'info> {
@@ -10,15 +12,15 @@ pub struct ReleasePayment<'info> {
         constraint = job.client == client.key() @ AgentProtocolError::Unauthorized
     )]
     pub client: Signer<'info>,
-    /// CHECK: Agent wallet receives payment. Validated against job.agent.
+    /// CHECK: Agent payout wallet receives payment. Validated against agent_profile.payout.
     #[account(
         mut,
-        constraint = job.agent == agent.key() @ AgentProtocolError::Unauthorized
+        constraint = agent_profile.payout == agent.key() @ AgentProtocolError::Unauthorized
     )]
     pub agent: AccountInfo<'info>,
     #[account(
         mut,
-        constraint = agent_profile.owner == job.agent @ AgentProtocolError::Unauthorized
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized
     )]
     pub agent_profile: Account<'info, AgentProfile>,
     #[account(mut)]
@@ -26,29 +28,79 @@ pub struct ReleasePayment<'info> {
     /// Optional parent job — required when job.parent_job is Some
     #[account(mut)]
     pub parent_job: Option<Account<'info, Job>>,
+    /// Team roster — required when job.team_size > 0; rent returns to the client
+    #[account(
+        mut,
+        seeds = [b"team", job.key().as_ref()],
+        bump = team.bump,
+        close = client
+    )]
+    pub team: Option<Account<'info, JobTeam>>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<ReleasePayment>) -> Result<()> {
-    let job = &mut ctx.accounts.job;
-    require!(job.status == JobStatus::Completed, AgentProtocolError::InvalidJobStatus);
+/// An optional bonus is moved from the client into the job and paid out with
+/// the escrow, split by share on team jobs.
+///
+/// For team jobs the other members are passed as remaining accounts in
+/// `[agent_profile, payout_wallet]` pairs, in roster order after the lead.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReleasePayment<'info>>,
+    bonus_lamports: Option<u64>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.job.status == JobStatus::Completed,
+        AgentProtocolError::InvalidJobStatus
+    );
+
+    // Transfer the bonus from client to Job PDA alongside the escrow
+    let bonus = bonus_lamports.unwrap_or(0);
+    if bonus > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.client.to_account_info(),
+                    to: ctx.accounts.job.to_account_info(),
+                },
+            ),
+            bonus,
+        )?;
+    }
+    ctx.accounts.job.bonus_lamports = bonus;
 
-    let escrow_amount = job.escrow_lamports;
+    // Team members are paid first; the lead (fixed accounts) takes the remainder
+    let (agent_amount, agent_bonus) = pay_team_members(
+        &ctx.accounts.job,
+        ctx.accounts.team.as_deref(),
+        &ctx.accounts.agent_profile,
+        ctx.remaining_accounts,
+        false,
+    )?;
+
+    let job = &mut ctx.accounts.job;
+    let margin_lamports = job.delegation_margin(agent_amount);
 
     // Terminal state — account will be closed in same instruction
     job.status = JobStatus::Finalized;
     job.escrow_lamports = 0;
 
-    // Transfer escrow to agent via direct lamport manipulation
+    // Transfer escrow and bonus to agent via direct lamport manipulation
+    let payout = agent_amount
+        .checked_add(agent_bonus)
+        .ok_or(AgentProtocolError::Overflow)?;
     let job_info = job.to_account_info();
     let agent_info = ctx.accounts.agent.to_account_info();
-    **job_info.try_borrow_mut_lamports()? -= escrow_amount;
-    **agent_info.try_borrow_mut_lamports()? += escrow_amount;
+    **job_info.try_borrow_mut_lamports()? -= payout;
+    **agent_info.try_borrow_mut_lamports()? += payout;
 
     // Update agent stats
     let profile = &mut ctx.accounts.agent_profile;
     profile.jobs_completed = profile.jobs_completed
         .checked_add(1)
         .ok_or(AgentProtocolError::Overflow)?;
+    profile.close_job()?;
+    profile.record_earnings(payout)?;
 
     // Handle parent decrement for child jobs
     if job.parent_job.is_some() {
@@ -62,14 +114,93 @@ pub fn handler(ctx: Context<ReleasePayment>) -> Result<()> {
         parent.active_children = parent.active_children
             .checked_sub(1)
             .ok_or(AgentProtocolError::Overflow)?;
+
+        // Link the child's result into the parent for auditing the tree
+        let result_hash = job.result_hash.unwrap_or_default();
+        parent.record_child_result(job.key(), result_hash)?;
+        emit!(ChildResultRecorded {
+            parent_job: parent.key(),
+            child_job: job.key(),
+            result_hash,
+            child_results_hash: parent.child_results_hash,
+        });
     }
 
     emit!(PaymentReleased {
         job: ctx.accounts.job.key(),
-        agent: ctx.accounts.agent.key(),
-        amount: escrow_amount,
+        agent: ctx.accounts.job.agent,
+        amount: agent_amount,
+        bonus: agent_bonus,
         auto_released: false,
+        margin_lamports,
     });
 
     Ok(())
 }
+
+/// Pays every non-lead member of a team job its share of the escrow and bonus
+/// and bumps its `jobs_completed`, emitting one PaymentReleased each. Shares
+/// apply to the escrow plus whatever the lead delegated out, so delegation is
+/// charged to the lead alone. Returns the `(escrow, bonus)` left for the lead,
+/// which absorbs rounding dust. Solo jobs return both in full.
+pub(crate) fn pay_team_members<'info>(
+    job: &Account<'info, Job>,
+    team: Option<&JobTeam>,
+    lead_profile: &Account<'info, AgentProfile>,
+    remaining: &'info [AccountInfo<'info>],
+    auto_released: bool,
+) -> Result<(u64, u64)> {
+    if job.team_size == 0 {
+        return Ok((job.escrow_lamports, job.bonus_lamports));
+    }
+    let team = team.ok_or(AgentProtocolError::TeamMismatch)?;
+    require!(
+        team.members[0].agent_profile == lead_profile.key()
+            && remaining.len() == (team.members.len() - 1) * 2,
+        AgentProtocolError::TeamMismatch
+    );
+
+    let gross = job.escrow_lamports
+        .checked_add(job.delegated_total)
+        .ok_or(AgentProtocolError::Overflow)?;
+    let job_info = job.to_account_info();
+    let mut paid: u64 = 0;
+    let mut bonus_paid: u64 = 0;
+    for (member, pair) in team.members.iter().skip(1).zip(remaining.chunks(2)) {
+        let (profile_info, wallet_info) = (&pair[0], &pair[1]);
+        require!(profile_info.key() == member.agent_profile, AgentProtocolError::TeamMismatch);
+        let mut profile = Account::<AgentProfile>::try_from(profile_info)?;
+        require!(wallet_info.key() == profile.payout, AgentProtocolError::TeamMismatch);
+
+        let amount = apply_bps(gross, member.share_bps)?;
+        let bonus = apply_bps(job.bonus_lamports, member.share_bps)?;
+        let payout = amount.checked_add(bonus).ok_or(AgentProtocolError::Overflow)?;
+        **job_info.try_borrow_mut_lamports()? -= payout;
+        **wallet_info.try_borrow_mut_lamports()? += payout;
+        paid = paid.checked_add(amount).ok_or(AgentProtocolError::Overflow)?;
+        bonus_paid = bonus_paid.checked_add(bonus).ok_or(AgentProtocolError::Overflow)?;
+
+        profile.jobs_completed = profile.jobs_completed
+            .checked_add(1)
+            .ok_or(AgentProtocolError::Overflow)?;
+        profile.record_earnings(payout)?;
+        profile.exit(&crate::ID)?;
+
+        emit!(PaymentReleased {
+            job: job.key(),
+            agent: member.agent,
+            amount,
+            bonus,
+            auto_released,
+            margin_lamports: None,
+        });
+    }
+
+    let lead_amount = job.escrow_lamports
+        .checked_sub(paid)
+        .ok_or(AgentProtocolError::InsufficientEscrow)?;
+    let lead_bonus = job.bonus_lamports
+        .checked_sub(bonus_paid)
+        .ok_or(AgentProtocolError::Overflow)?;
+    Ok((lead_amount, lead_bonus))
+}
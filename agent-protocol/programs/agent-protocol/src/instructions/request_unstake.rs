@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, AgentStake};
+use crate::error::AgentProtocolError;
+use crate::events::UnstakeRequested;
+use crate::constants::UNSTAKE_COOLDOWN;
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        constraint = agent_profile.owner == owner.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    #[account(
+        mut,
+        seeds = [b"stake", agent_profile.key().as_ref()],
+        bump = agent_stake.bump
+    )]
+    pub agent_stake: Account<'info, AgentStake>,
+}
+
+pub fn handler(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentProtocolError::InvalidStakeAmount);
+
+    let stake = &mut ctx.accounts.agent_stake;
+    stake.staked_lamports = stake.staked_lamports
+        .checked_sub(amount)
+        .ok_or(AgentProtocolError::InsufficientStake)?;
+    stake.unstaking_lamports = stake.unstaking_lamports
+        .checked_add(amount)
+        .ok_or(AgentProtocolError::Overflow)?;
+
+    // A new request restarts the cooldown for the whole queued amount
+    let available_at = Clock::get()?.unix_timestamp
+        .checked_add(UNSTAKE_COOLDOWN)
+        .ok_or(AgentProtocolError::Overflow)?;
+    stake.unstake_available_at = Some(available_at);
+
+    emit!(UnstakeRequested {
+        agent: ctx.accounts.agent_profile.key(),
+        amount,
+        available_at,
+    });
+
+    Ok(())
+}
@@ -1,12 +1,13 @@
 use anchor_lang::prelude::*;
-use crate::state::{Job, JobStatus};
+use crate::state::{AgentProfile, AgentStake, Job, JobExtension, JobStatus, JobTeam};
 use crate::error::AgentProtocolError;
-use crate::events::DisputeResolved;
+use crate::events::{DisputeResolved, StakeSlashed};
 use crate::constants::DISPUTE_TIMEOUT;
+use crate::math::apply_bps;
 
 #[derive(Accounts)]
 pub struct ResolveDispute<'info> {
-    /// CHECK: Client receives refund. Validated against job.client.
+    /// CHECK: Client receives refund (the parent job does for child jobs) and any slash. Validated against job.client.
     #[account(
         mut,
         constraint = job.client == client.key() @ AgentProtocolError::Unauthorized
@@ -17,12 +18,53 @@ pub struct ResolveDispute<'info> {
         close = client
     )]
     pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    /// CHECK: Agent stake vault, always derived from the profile so the caller
+    /// cannot dodge slashing by omitting it. Slashed only if initialized.
+    #[account(
+        mut,
+        seeds = [b"stake", agent_profile.key().as_ref()],
+        bump
+    )]
+    pub agent_stake: UncheckedAccount<'info>,
+    /// Optional parent job — required when job.parent_job is Some
+    #[account(mut)]
+    pub parent_job: Option<Account<'info, Job>>,
+    /// Team roster — required when job.team_size > 0; rent returns to the client
+    #[account(
+        mut,
+        seeds = [b"team", job.key().as_ref()],
+        bump = team.bump,
+        close = client
+    )]
+    pub team: Option<Account<'info, JobTeam>>,
+    /// Extension PDA — required when job.has_extension; rent returns to the client
+    #[account(
+        mut,
+        seeds = [b"job_ext", job.key().as_ref()],
+        bump = job_extension.bump,
+        close = client
+    )]
+    pub job_extension: Option<Account<'info, JobExtension>>,
 }
 
 pub fn handler(ctx: Context<ResolveDispute>) -> Result<()> {
     let job = &mut ctx.accounts.job;
     require!(job.status == JobStatus::Disputed, AgentProtocolError::InvalidJobStatus);
     require!(job.disputed_at.is_some(), AgentProtocolError::InvalidJobStatus);
+    // Open children point back at this job, which is about to be closed
+    require!(job.active_children == 0, AgentProtocolError::UnresolvedChildren);
+    // The roster is closed with the job, so it has to be passed in
+    require!(
+        job.team_size == 0 || ctx.accounts.team.is_some(),
+        AgentProtocolError::TeamMismatch
+    );
+    // The extension is closed with the job, so it has to be passed in
+    job.check_extension(ctx.accounts.job_extension.as_deref())?;
 
     let clock = Clock::get()?;
     require!(
@@ -31,19 +73,75 @@ pub fn handler(ctx: Context<ResolveDispute>) -> Result<()> {
     );
 
     let refund_amount = job.escrow_lamports;
+    // Only a dispute the client raised puts the agent's stake at risk, and
+    // never for more than the job was worth: the client already gets the
+    // escrow back, so a larger slash would pay it to dispute
+    let client_raised = job.disputed_by == Some(job.client);
+    let slash_cap = if client_raised { job.original_escrow } else { 0 };
+    let slash_bps = job.slash_bps;
 
     // Terminal state — account will be closed in same instruction
     job.status = JobStatus::Cancelled;
     job.escrow_lamports = 0;
 
-    // Refund escrow to client via direct lamport manipulation
+    // A child's escrow goes back into its parent, as in cancel_job
+    let refund_info = match job.parent_job {
+        Some(parent_key) => {
+            let parent = ctx.accounts.parent_job.as_mut()
+                .ok_or(AgentProtocolError::ParentJobMismatch)?;
+            require!(parent_key == parent.key(), AgentProtocolError::ParentJobMismatch);
+            parent.reclaim_child(refund_amount)?;
+            parent.to_account_info()
+        }
+        None => ctx.accounts.client.to_account_info(),
+    };
+
+    // Refund escrow via direct lamport manipulation
     let job_info = job.to_account_info();
     let client_info = ctx.accounts.client.to_account_info();
     **job_info.try_borrow_mut_lamports()? -= refund_amount;
-    **client_info.try_borrow_mut_lamports()? += refund_amount;
+    **refund_info.try_borrow_mut_lamports()? += refund_amount;
+
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.close_job()?;
+    if client_raised {
+        profile.open_disputes = profile.open_disputes
+            .checked_sub(1)
+            .ok_or(AgentProtocolError::Overflow)?;
+    }
+
+    // Slash the agent's collateral (active + queued unstake) to the client
+    let stake_info = ctx.accounts.agent_stake.to_account_info();
+    if stake_info.owner == &crate::ID && !stake_info.data_is_empty() {
+        let mut stake = AgentStake::try_deserialize(&mut &stake_info.try_borrow_data()?[..])?;
+        let total = stake.staked_lamports
+            .checked_add(stake.unstaking_lamports)
+            .ok_or(AgentProtocolError::Overflow)?;
+        let slash_amount = apply_bps(total, slash_bps)?.min(slash_cap);
+
+        if slash_amount > 0 {
+            // Take from active stake first, then from the unstake queue
+            let from_staked = slash_amount.min(stake.staked_lamports);
+            stake.staked_lamports -= from_staked;
+            stake.unstaking_lamports = stake.unstaking_lamports
+                .checked_sub(slash_amount - from_staked)
+                .ok_or(AgentProtocolError::Overflow)?;
+            stake.try_serialize(&mut &mut stake_info.try_borrow_mut_data()?[..])?;
+
+            **stake_info.try_borrow_mut_lamports()? -= slash_amount;
+            **client_info.try_borrow_mut_lamports()? += slash_amount;
+
+            emit!(StakeSlashed {
+                agent: ctx.accounts.agent_profile.key(),
+                job: ctx.accounts.job.key(),
+                client: ctx.accounts.client.key(),
+                amount: slash_amount,
+            });
+        }
+    }
 
     emit!(DisputeResolved {
-        job: job.key(),
+        job: ctx.accounts.job.key(),
         refund_lamports: refund_amount,
     });
 
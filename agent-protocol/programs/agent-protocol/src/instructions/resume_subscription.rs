@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::Subscription;
+use crate::error::AgentProtocolError;
+use crate::events::SubscriptionResumed;
+
+#[derive(Accounts)]
+pub struct ResumeSubscription<'info> {
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        constraint = subscription.client == client.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub subscription: Account<'info, Subscription>,
+}
+
+pub fn handler(ctx: Context<ResumeSubscription>) -> Result<()> {
+    let sub = &mut ctx.accounts.subscription;
+    let paused_at = sub.paused_at.ok_or(AgentProtocolError::SubscriptionNotPaused)?;
+
+    // Shift the whole schedule by the pause length. Periods that had elapsed
+    // before the pause land at or before now, so they stay claimable.
+    let paused_for = Clock::get()?.unix_timestamp - paused_at;
+    sub.next_claim_at = sub.next_claim_at
+        .checked_add(paused_for)
+        .ok_or(AgentProtocolError::Overflow)?;
+    sub.paused_at = None;
+
+    emit!(SubscriptionResumed {
+        subscription: sub.key(),
+        client: ctx.accounts.client.key(),
+        next_claim_at: sub.next_claim_at,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::state::{Bid, JobRequest};
+use crate::error::AgentProtocolError;
+use crate::events::BidRevealed;
+
+#[derive(Accounts)]
+pub struct RevealBid<'info> {
+    pub agent: Signer<'info>,
+    pub job_request: Account<'info, JobRequest>,
+    #[account(
+        mut,
+        constraint = bid.agent == agent.key() @ AgentProtocolError::Unauthorized,
+        constraint = bid.job_request == job_request.key() @ AgentProtocolError::JobRequestMismatch
+    )]
+    pub bid: Account<'info, Bid>,
+}
+
+pub fn handler(
+    ctx: Context<RevealBid>,
+    price_lamports: u64,
+    eta_seconds: i64,
+    proposal_uri: String,
+    salt: [u8; 32],
+) -> Result<()> {
+    let request = &ctx.accounts.job_request;
+    let bid = &mut ctx.accounts.bid;
+    require!(request.sealed && !bid.revealed, AgentProtocolError::InvalidBid);
+
+    // Reveals are only accepted between the bid deadline and the reveal deadline
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= request.bid_deadline, AgentProtocolError::BiddingStillOpen);
+    let reveal_deadline = request.reveal_deadline.ok_or(AgentProtocolError::InvalidBid)?;
+    require!(now <= reveal_deadline, AgentProtocolError::BiddingClosed);
+
+    require!(proposal_uri.len() <= 128, AgentProtocolError::UriTooLong);
+    require!(
+        bid.commitment == Some(Bid::commitment_hash(price_lamports, eta_seconds, &proposal_uri, &salt)),
+        AgentProtocolError::BidCommitmentMismatch
+    );
+    require!(price_lamports > 0, AgentProtocolError::InvalidPrice);
+    require!(
+        price_lamports <= request.max_budget_lamports,
+        AgentProtocolError::BidExceedsBudget
+    );
+
+    bid.price_lamports = price_lamports;
+    bid.eta_seconds = eta_seconds;
+    bid.proposal_uri = proposal_uri;
+    bid.revealed = true;
+
+    emit!(BidRevealed {
+        job_request: request.key(),
+        bid: bid.key(),
+        agent: bid.agent,
+        price_lamports,
+        eta_seconds,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::state::{AccessMode, AgentAccessList, AgentProfile};
+use crate::error::AgentProtocolError;
+use crate::events::AccessListUpdated;
+use crate::constants::MAX_ACCESS_LIST_ENTRIES;
+
+#[derive(Accounts)]
+pub struct SetAccessList<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        constraint = agent_profile.owner == owner.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + AgentAccessList::INIT_SPACE,
+        seeds = [b"access", agent_profile.key().as_ref()],
+        bump
+    )]
+    pub access_list: Account<'info, AgentAccessList>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Replace the agent's access list. `Open` lifts the restriction without
+/// closing the PDA.
+pub fn handler(ctx: Context<SetAccessList>, mode: AccessMode, clients: Vec<Pubkey>) -> Result<()> {
+    require!(
+        clients.len() <= MAX_ACCESS_LIST_ENTRIES,
+        AgentProtocolError::AccessListTooLong
+    );
+
+    let list = &mut ctx.accounts.access_list;
+    list.agent_profile = ctx.accounts.agent_profile.key();
+    list.mode = mode;
+    list.clients = clients.clone();
+    list.bump = ctx.bumps.access_list;
+
+    emit!(AccessListUpdated {
+        agent: ctx.accounts.agent_profile.key(),
+        owner: ctx.accounts.owner.key(),
+        mode,
+        clients,
+    });
+
+    Ok(())
+}
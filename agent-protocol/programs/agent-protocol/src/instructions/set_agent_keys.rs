@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::AgentProfile;
+use crate::error::AgentProtocolError;
+use crate::events::AgentKeysUpdated;
+
+#[derive(Accounts)]
+pub struct SetAgentKeys<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.owner == owner.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+}
+
+/// Point payouts at a separate wallet and optionally authorize a hot key to
+/// run the agent's jobs. Passing `operator: None` revokes the current one.
+pub fn handler(
+    ctx: Context<SetAgentKeys>,
+    payout: Pubkey,
+    operator: Option<Pubkey>,
+) -> Result<()> {
+    require!(payout != Pubkey::default(), AgentProtocolError::InvalidPayoutWallet);
+
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.payout = payout;
+    profile.operator = operator;
+
+    emit!(AgentKeysUpdated {
+        agent: profile.key(),
+        owner: ctx.accounts.owner.key(),
+        payout,
+        operator,
+    });
+
+    Ok(())
+}
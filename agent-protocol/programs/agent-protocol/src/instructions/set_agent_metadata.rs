@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentMetadata, AgentProfile};
+use crate::error::AgentProtocolError;
+use crate::events::AgentMetadataUpdated;
+
+#[derive(Accounts)]
+pub struct SetAgentMetadata<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        constraint = agent_profile.owner == owner.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + AgentMetadata::INIT_SPACE,
+        seeds = [b"metadata", agent_profile.key().as_ref()],
+        bump
+    )]
+    pub agent_metadata: Account<'info, AgentMetadata>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Publish or replace the agent's endpoint and metadata. The first call
+/// creates the metadata PDA, paid by the owner.
+pub fn handler(
+    ctx: Context<SetAgentMetadata>,
+    endpoint_uri: String,
+    metadata_uri: String,
+    metadata_hash: [u8; 32],
+    input_formats: u16,
+    schema_version: u8,
+) -> Result<()> {
+    AgentMetadata::validate(&endpoint_uri, &metadata_uri, &metadata_hash, schema_version)?;
+
+    let metadata = &mut ctx.accounts.agent_metadata;
+    metadata.agent_profile = ctx.accounts.agent_profile.key();
+    metadata.endpoint_uri = endpoint_uri.clone();
+    metadata.metadata_uri = metadata_uri.clone();
+    metadata.metadata_hash = metadata_hash;
+    metadata.input_formats = input_formats;
+    metadata.schema_version = schema_version;
+    metadata.updated_at = Clock::get()?.unix_timestamp;
+    metadata.bump = ctx.bumps.agent_metadata;
+
+    emit!(AgentMetadataUpdated {
+        agent: ctx.accounts.agent_profile.key(),
+        owner: ctx.accounts.owner.key(),
+        endpoint_uri,
+        metadata_uri,
+        metadata_hash,
+        input_formats,
+        schema_version,
+    });
+
+    Ok(())
+}
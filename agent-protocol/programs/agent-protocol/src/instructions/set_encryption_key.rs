@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::state::AgentProfile;
+use crate::error::AgentProtocolError;
+use crate::events::EncryptionKeyUpdated;
+
+#[derive(Accounts)]
+pub struct SetEncryptionKey<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.owner == owner.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+}
+
+/// Publish, rotate or clear the agent's X25519 key. Rotation does not affect
+/// results already delivered: each envelope names the agent key it was made with.
+pub fn handler(ctx: Context<SetEncryptionKey>, encryption_key: Option<[u8; 32]>) -> Result<()> {
+    if let Some(key) = encryption_key {
+        require!(key != [0u8; 32], AgentProtocolError::InvalidEncryptionKey);
+    }
+
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.encryption_key = encryption_key;
+
+    emit!(EncryptionKeyUpdated {
+        agent: profile.key(),
+        owner: ctx.accounts.owner.key(),
+        encryption_key,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::state::AgentProfile;
+use crate::error::AgentProtocolError;
+use crate::events::AgentCapacityUpdated;
+
+#[derive(Accounts)]
+pub struct SetMaxConcurrentJobs<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.owner == owner.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+}
+
+/// Cap the jobs this agent takes on at once; 0 removes the cap. Lowering it
+/// below `active_jobs` only blocks new work until jobs finish.
+pub fn handler(ctx: Context<SetMaxConcurrentJobs>, max_concurrent_jobs: u16) -> Result<()> {
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.max_concurrent_jobs = max_concurrent_jobs;
+
+    emit!(AgentCapacityUpdated {
+        agent: profile.key(),
+        owner: ctx.accounts.owner.key(),
+        max_concurrent_jobs,
+        active_jobs: profile.active_jobs,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, CapabilityPrice};
+use crate::error::AgentProtocolError;
+use crate::events::PriceTableUpdated;
+
+#[derive(Accounts)]
+pub struct SetPriceTable<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.owner == owner.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+}
+
+/// Replace the agent's price table; an empty table leaves `price_lamports` as
+/// the only price. Jobs already escrowed keep the price they were opened at.
+pub fn handler(ctx: Context<SetPriceTable>, price_table: Vec<CapabilityPrice>) -> Result<()> {
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.validate_price_table(&price_table)?;
+    profile.price_table = price_table.clone();
+
+    emit!(PriceTableUpdated {
+        agent: profile.key(),
+        owner: ctx.accounts.owner.key(),
+        price_table,
+    });
+
+    Ok(())
+}
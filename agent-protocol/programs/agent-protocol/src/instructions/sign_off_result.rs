@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, Job, JobStatus, JobTeam};
+use crate::error::AgentProtocolError;
+use crate::events::{JobCompleted, TeamMemberSignedOff};
+
+#[derive(Accounts)]
+pub struct SignOffResult<'info> {
+    pub member: Signer<'info>,
+    #[account(
+        constraint = member_profile.owner == member.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub member_profile: Account<'info, AgentProfile>,
+    #[account(mut)]
+    pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [b"team", job.key().as_ref()],
+        bump = team.bump
+    )]
+    pub team: Account<'info, JobTeam>,
+}
+
+/// A non-lead member approves the lead's delivery. The last sign-off moves
+/// the job to Completed, which is when JobCompleted is emitted.
+pub fn handler(ctx: Context<SignOffResult>) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+    // Delivered but still waiting on the team
+    require!(
+        job.status == JobStatus::InProgress && !job.result_uri.is_empty(),
+        AgentProtocolError::InvalidJobStatus
+    );
+
+    let member_profile = ctx.accounts.member_profile.key();
+    let member = ctx.accounts.team.members
+        .iter_mut()
+        .skip(1)
+        .find(|m| m.agent_profile == member_profile)
+        .ok_or(AgentProtocolError::NotTeamMember)?;
+    require!(!member.signed_off, AgentProtocolError::AlreadySignedOff);
+    member.signed_off = true;
+
+    job.pending_signoffs = job.pending_signoffs
+        .checked_sub(1)
+        .ok_or(AgentProtocolError::Overflow)?;
+
+    emit!(TeamMemberSignedOff {
+        job: job.key(),
+        agent: member.agent,
+        pending_signoffs: job.pending_signoffs,
+    });
+
+    if job.pending_signoffs == 0 {
+        job.status = JobStatus::Completed;
+        job.completed_at = Some(Clock::get()?.unix_timestamp);
+
+        emit!(JobCompleted {
+            job: job.key(),
+            agent: job.agent,
+            result_uri: job.result_uri.clone(),
+            result_hash: job.result_hash.unwrap_or_default(),
+            // Team jobs are created without an extension, so never encrypted
+            envelope: None,
+            child_results_hash: job.child_results_hash,
+            children_completed: job.children_completed,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{AgentProfile, AgentStake};
+use crate::error::AgentProtocolError;
+use crate::events::AgentStaked;
+
+#[derive(Accounts)]
+pub struct StakeAgent<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        constraint = agent_profile.owner == owner.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + AgentStake::INIT_SPACE,
+        seeds = [b"stake", agent_profile.key().as_ref()],
+        bump
+    )]
+    pub agent_stake: Account<'info, AgentStake>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<StakeAgent>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentProtocolError::InvalidStakeAmount);
+
+    // Transfer SOL from owner to the stake vault PDA
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.agent_stake.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let stake = &mut ctx.accounts.agent_stake;
+    stake.agent_profile = ctx.accounts.agent_profile.key();
+    stake.bump = ctx.bumps.agent_stake;
+    stake.staked_lamports = stake.staked_lamports
+        .checked_add(amount)
+        .ok_or(AgentProtocolError::Overflow)?;
+
+    emit!(AgentStaked {
+        agent: ctx.accounts.agent_profile.key(),
+        owner: ctx.accounts.owner.key(),
+        amount,
+        total_staked: stake.staked_lamports,
+    });
+
+    Ok(())
+}
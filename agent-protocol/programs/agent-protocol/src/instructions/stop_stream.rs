@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, Job, JobExtension, JobStatus};
+use crate::error::AgentProtocolError;
+use crate::events::StreamStopped;
+
+#[derive(Accounts)]
+pub struct StopStream<'info> {
+    #[account(
+        mut,
+        constraint = job.client == client.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub client: Signer<'info>,
+    /// CHECK: Agent payout wallet receives vested lamports. Validated against agent_profile.payout.
+    #[account(
+        mut,
+        constraint = agent_profile.payout == agent.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    #[account(mut)]
+    pub job: Account<'info, Job>,
+    /// Stream schedule — required when job.has_extension
+    #[account(
+        mut,
+        seeds = [b"job_ext", job.key().as_ref()],
+        bump = job_extension.bump
+    )]
+    pub job_extension: Option<Account<'info, JobExtension>>,
+}
+
+pub fn handler(ctx: Context<StopStream>) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+    require!(job.status != JobStatus::Disputed, AgentProtocolError::StreamFrozen);
+    require!(
+        job.status == JobStatus::Pending
+            || job.status == JobStatus::InProgress
+            || job.status == JobStatus::Completed,
+        AgentProtocolError::InvalidJobStatus
+    );
+
+    job.check_extension(ctx.accounts.job_extension.as_deref())?;
+    let extension = ctx.accounts.job_extension.as_mut()
+        .ok_or(AgentProtocolError::NotStreamingJob)?;
+    let mut stream = extension.stream.ok_or(AgentProtocolError::NotStreamingJob)?;
+    let agent_payout = stream.withdrawable(Clock::get()?.unix_timestamp);
+    let refund_amount = job.escrow_lamports
+        .checked_sub(agent_payout)
+        .ok_or(AgentProtocolError::InsufficientEscrow)?;
+
+    // Terminal state — nothing further vests
+    stream.withdrawn_lamports = stream.withdrawn_lamports
+        .checked_add(agent_payout)
+        .ok_or(AgentProtocolError::Overflow)?;
+    extension.stream = Some(stream);
+    // A stream that never paid anything is a cancellation, not a delivery
+    job.status = if stream.withdrawn_lamports > 0 {
+        JobStatus::Finalized
+    } else {
+        JobStatus::Cancelled
+    };
+    job.escrow_lamports = 0;
+
+    // Vested share to the agent, unvested remainder back to the client
+    let job_info = job.to_account_info();
+    let agent_info = ctx.accounts.agent.to_account_info();
+    let client_info = ctx.accounts.client.to_account_info();
+    **job_info.try_borrow_mut_lamports()? -= agent_payout;
+    **agent_info.try_borrow_mut_lamports()? += agent_payout;
+    **job_info.try_borrow_mut_lamports()? -= refund_amount;
+    **client_info.try_borrow_mut_lamports()? += refund_amount;
+
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.close_job()?;
+    profile.record_earnings(agent_payout)?;
+
+    emit!(StreamStopped {
+        job: ctx.accounts.job.key(),
+        client: ctx.accounts.client.key(),
+        agent_payout,
+        refund_lamports: refund_amount,
+    });
+
+    Ok(())
+}
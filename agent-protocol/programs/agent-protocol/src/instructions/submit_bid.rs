@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, Bid, JobRequest};
+use crate::error::AgentProtocolError;
+use crate::events::BidSubmitted;
+
+#[derive(Accounts)]
+pub struct SubmitBid<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+    #[account(
+        constraint = agent_profile.owner == agent.key() @ AgentProtocolError::Unauthorized,
+        constraint = agent_profile.is_active @ AgentProtocolError::AgentNotActive
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    #[account(mut)]
+    pub job_request: Account<'info, JobRequest>,
+    #[account(
+        init,
+        payer = agent,
+        space = 8 + Bid::INIT_SPACE,
+        seeds = [b"bid", job_request.key().as_ref(), agent_profile.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Open requests take the quote in the clear; sealed requests take only the
+/// commitment and expect price, ETA and proposal URI to be left zeroed.
+pub fn handler(
+    ctx: Context<SubmitBid>,
+    price_lamports: u64,
+    eta_seconds: i64,
+    proposal_uri: String,
+    commitment: Option<[u8; 32]>,
+) -> Result<()> {
+    let request = &mut ctx.accounts.job_request;
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp < request.bid_deadline, AgentProtocolError::BiddingClosed);
+    require!(proposal_uri.len() <= 128, AgentProtocolError::UriTooLong);
+
+    if request.sealed {
+        require!(
+            commitment.is_some() && price_lamports == 0 && eta_seconds == 0 && proposal_uri.is_empty(),
+            AgentProtocolError::InvalidBid
+        );
+    } else {
+        require!(commitment.is_none(), AgentProtocolError::InvalidBid);
+        require!(price_lamports > 0, AgentProtocolError::InvalidPrice);
+        require!(
+            price_lamports <= request.max_budget_lamports,
+            AgentProtocolError::BidExceedsBudget
+        );
+    }
+
+    request.bid_count = request.bid_count
+        .checked_add(1)
+        .ok_or(AgentProtocolError::Overflow)?;
+
+    let bid = &mut ctx.accounts.bid;
+    bid.job_request = request.key();
+    bid.agent = ctx.accounts.agent.key();
+    bid.agent_profile = ctx.accounts.agent_profile.key();
+    bid.price_lamports = price_lamports;
+    bid.eta_seconds = eta_seconds;
+    bid.proposal_uri = proposal_uri;
+    bid.commitment = commitment;
+    bid.revealed = !request.sealed;
+    bid.created_at = clock.unix_timestamp;
+    bid.bump = ctx.bumps.bid;
+
+    emit!(BidSubmitted {
+        job_request: request.key(),
+        bid: bid.key(),
+        agent: bid.agent,
+        price_lamports,
+        sealed: request.sealed,
+    });
+
+    Ok(())
+}
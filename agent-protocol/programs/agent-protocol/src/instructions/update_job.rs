@@ -1,20 +1,38 @@
 use anchor_lang::prelude::*;
-use crate::state::{Job, JobStatus};
+use crate::state::{AgentProfile, Job, JobExtension, JobStatus};
+use crate::envelope::ResultEnvelope;
 use crate::error::AgentProtocolError;
 use crate::events::JobCompleted;
 
 #[derive(Accounts)]
 pub struct UpdateJob<'info> {
+    /// The assigned agent's owner or operator
     pub agent: Signer<'info>,
+    #[account(mut)]
+    pub job: Account<'info, Job>,
+    #[account(
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized,
+        constraint = agent_profile.can_operate(agent.key()) @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    /// Holds the client's encryption key and receives the envelope — required
+    /// when job.has_extension
     #[account(
         mut,
-        constraint = job.agent == agent.key() @ AgentProtocolError::Unauthorized
+        seeds = [b"job_ext", job.key().as_ref()],
+        bump = job_extension.bump
     )]
-    pub job: Account<'info, Job>,
+    pub job_extension: Option<Account<'info, JobExtension>>,
 }
 
-pub fn handler(ctx: Context<UpdateJob>, result_uri: String) -> Result<()> {
+pub fn handler(
+    ctx: Context<UpdateJob>,
+    result_uri: String,
+    result_hash: [u8; 32],
+    envelope: Option<ResultEnvelope>,
+) -> Result<()> {
     require!(!result_uri.is_empty(), AgentProtocolError::EmptyResultUri);
+    require!(result_hash != [0u8; 32], AgentProtocolError::EmptyResultHash);
 
     let job = &mut ctx.accounts.job;
 
@@ -23,6 +41,32 @@ pub fn handler(ctx: Context<UpdateJob>, result_uri: String) -> Result<()> {
         AgentProtocolError::InvalidJobStatus
     );
     require!(job.active_children == 0, AgentProtocolError::UnresolvedChildren);
+    // Team members sign off on one delivery; the lead cannot swap it afterwards
+    require!(
+        job.team_size == 0 || job.result_uri.is_empty(),
+        AgentProtocolError::ResultAlreadyCommitted
+    );
+
+    // Commit-reveal: the revealed URI must point at the content committed earlier
+    if let Some(committed) = job.result_hash {
+        require!(committed == result_hash, AgentProtocolError::ResultHashMismatch);
+    }
+
+    // Encrypted delivery: the envelope must wrap the content key for the client
+    job.check_extension(ctx.accounts.job_extension.as_deref())?;
+    let client_encryption_key = ctx.accounts.job_extension
+        .as_ref()
+        .and_then(|extension| extension.client_encryption_key);
+    match (client_encryption_key, envelope.as_ref()) {
+        (Some(_), Some(envelope)) => {
+            let agent_key = ctx.accounts.agent_profile.encryption_key
+                .ok_or(AgentProtocolError::EncryptionKeyMissing)?;
+            envelope.validate(&agent_key)?;
+        }
+        (Some(_), None) => return err!(AgentProtocolError::EnvelopeRequired),
+        (None, Some(_)) => return err!(AgentProtocolError::InvalidEnvelope),
+        (None, None) => {}
+    }
 
     // Auto-flip Pending → InProgress before completing
     if job.status == JobStatus::Pending {
@@ -30,13 +74,27 @@ pub fn handler(ctx: Context<UpdateJob>, result_uri: String) -> Result<()> {
     }
 
     job.result_uri = result_uri.clone();
+    job.result_hash = Some(result_hash);
+    if let Some(extension) = ctx.accounts.job_extension.as_mut() {
+        extension.result_envelope = envelope;
+    }
+
+    // Team jobs wait in InProgress until every member signs off (sign_off_result)
+    if job.pending_signoffs > 0 {
+        return Ok(());
+    }
+
     job.status = JobStatus::Completed;
     job.completed_at = Some(Clock::get()?.unix_timestamp);
 
     emit!(JobCompleted {
         job: job.key(),
-        agent: ctx.accounts.agent.key(),
+        agent: job.agent,
         result_uri,
+        result_hash,
+        envelope,
+        child_results_hash: job.child_results_hash,
+        children_completed: job.children_completed,
     });
 
     Ok(())
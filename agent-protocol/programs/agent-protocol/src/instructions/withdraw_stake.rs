@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, AgentStake};
+use crate::error::AgentProtocolError;
+use crate::events::StakeWithdrawn;
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        constraint = agent_profile.owner == owner.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    #[account(
+        mut,
+        seeds = [b"stake", agent_profile.key().as_ref()],
+        bump = agent_stake.bump
+    )]
+    pub agent_stake: Account<'info, AgentStake>,
+}
+
+pub fn handler(ctx: Context<WithdrawStake>) -> Result<()> {
+    // A pending client dispute may still slash the queued lamports
+    require!(
+        ctx.accounts.agent_profile.open_disputes == 0,
+        AgentProtocolError::DisputesOpen
+    );
+
+    let stake = &mut ctx.accounts.agent_stake;
+    require!(stake.unstaking_lamports > 0, AgentProtocolError::NothingToWithdraw);
+
+    let available_at = stake.unstake_available_at
+        .ok_or(AgentProtocolError::NothingToWithdraw)?;
+    require!(
+        Clock::get()?.unix_timestamp >= available_at,
+        AgentProtocolError::UnstakeCooldownActive
+    );
+
+    let amount = stake.unstaking_lamports;
+    stake.unstaking_lamports = 0;
+    stake.unstake_available_at = None;
+
+    // Pay out from the stake vault via direct lamport manipulation
+    let stake_info = stake.to_account_info();
+    let owner_info = ctx.accounts.owner.to_account_info();
+    **stake_info.try_borrow_mut_lamports()? -= amount;
+    **owner_info.try_borrow_mut_lamports()? += amount;
+
+    emit!(StakeWithdrawn {
+        agent: ctx.accounts.agent_profile.key(),
+        owner: ctx.accounts.owner.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, Job, JobExtension, JobStatus};
+use crate::error::AgentProtocolError;
+use crate::events::StreamWithdrawn;
+
+#[derive(Accounts)]
+pub struct WithdrawStream<'info> {
+    #[account(
+        constraint = agent_profile.owner == agent.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub agent: Signer<'info>,
+    #[account(
+        mut,
+        constraint = agent_profile.agent_id == job.agent @ AgentProtocolError::Unauthorized
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    /// CHECK: Agent payout wallet receives vested lamports. Validated against agent_profile.payout.
+    #[account(
+        mut,
+        constraint = agent_profile.payout == payout.key() @ AgentProtocolError::Unauthorized
+    )]
+    pub payout: AccountInfo<'info>,
+    #[account(mut)]
+    pub job: Account<'info, Job>,
+    /// Stream schedule — required when job.has_extension
+    #[account(
+        mut,
+        seeds = [b"job_ext", job.key().as_ref()],
+        bump = job_extension.bump
+    )]
+    pub job_extension: Option<Account<'info, JobExtension>>,
+}
+
+pub fn handler(ctx: Context<WithdrawStream>) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+    require!(job.status != JobStatus::Disputed, AgentProtocolError::StreamFrozen);
+    require!(
+        job.status == JobStatus::Pending
+            || job.status == JobStatus::InProgress
+            || job.status == JobStatus::Completed,
+        AgentProtocolError::InvalidJobStatus
+    );
+
+    job.check_extension(ctx.accounts.job_extension.as_deref())?;
+    let extension = ctx.accounts.job_extension.as_mut()
+        .ok_or(AgentProtocolError::NotStreamingJob)?;
+    let mut stream = extension.stream.ok_or(AgentProtocolError::NotStreamingJob)?;
+    let amount = stream.withdrawable(Clock::get()?.unix_timestamp);
+    require!(amount > 0, AgentProtocolError::NothingToWithdraw);
+
+    stream.withdrawn_lamports = stream.withdrawn_lamports
+        .checked_add(amount)
+        .ok_or(AgentProtocolError::Overflow)?;
+    extension.stream = Some(stream);
+    job.escrow_lamports = job.escrow_lamports
+        .checked_sub(amount)
+        .ok_or(AgentProtocolError::InsufficientEscrow)?;
+
+    // First withdrawal locks the job against cancellation
+    if job.status == JobStatus::Pending {
+        job.status = JobStatus::InProgress;
+    }
+
+    // Fully streamed — terminal state
+    let fully_streamed = stream.withdrawn_lamports == stream.total_lamports;
+    if fully_streamed {
+        job.status = JobStatus::Finalized;
+    }
+
+    // Transfer vested lamports via direct lamport manipulation
+    let job_info = job.to_account_info();
+    let agent_info = ctx.accounts.payout.to_account_info();
+    **job_info.try_borrow_mut_lamports()? -= amount;
+    **agent_info.try_borrow_mut_lamports()? += amount;
+
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.record_earnings(amount)?;
+    if fully_streamed {
+        profile.jobs_completed = profile.jobs_completed
+            .checked_add(1)
+            .ok_or(AgentProtocolError::Overflow)?;
+        profile.close_job()?;
+    }
+
+    emit!(StreamWithdrawn {
+        job: ctx.accounts.job.key(),
+        agent: ctx.accounts.job.agent,
+        amount,
+        withdrawn_total: stream.withdrawn_lamports,
+    });
+
+    Ok(())
+}
@@ -1,9 +1,11 @@
 use anchor_lang::prelude::*;
 
 pub mod constants;
+pub mod envelope;
 pub mod error;
 pub mod events;
 pub mod instructions;
+pub mod math;
 pub mod state;
 
 use instructions::*;
@@ -26,23 +28,34 @@ pub mod agent_protocol {
 
     pub fn invoke_agent(
         ctx: Context<InvokeAgent>,
-        description: String,
+        title: String,
         payment_lamports: u64,
-        auto_release_seconds: Option<i64>,
-        timestamp_seed: i64,
+        options: state::InvokeOptions,
     ) -> Result<()> {
-        instructions::invoke_agent::handler(ctx, description, payment_lamports, auto_release_seconds, timestamp_seed)
+        instructions::invoke_agent::handler(ctx, title, payment_lamports, options)
     }
 
-    pub fn update_job(ctx: Context<UpdateJob>, result_uri: String) -> Result<()> {
-        instructions::update_job::handler(ctx, result_uri)
+    pub fn update_job(
+        ctx: Context<UpdateJob>,
+        result_uri: String,
+        result_hash: [u8; 32],
+        envelope: Option<envelope::ResultEnvelope>,
+    ) -> Result<()> {
+        instructions::update_job::handler(ctx, result_uri, result_hash, envelope)
     }
 
-    pub fn release_payment(ctx: Context<ReleasePayment>) -> Result<()> {
-        instructions::release_payment::handler(ctx)
+    pub fn commit_result(ctx: Context<CommitResult>, result_hash: [u8; 32]) -> Result<()> {
+        instructions::commit_result::handler(ctx, result_hash)
     }
 
-    pub fn auto_release(ctx: Context<AutoRelease>) -> Result<()> {
+    pub fn release_payment<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReleasePayment<'info>>,
+        bonus_lamports: Option<u64>,
+    ) -> Result<()> {
+        instructions::release_payment::handler(ctx, bonus_lamports)
+    }
+
+    pub fn auto_release<'info>(ctx: Context<'_, '_, 'info, 'info, AutoRelease<'info>>) -> Result<()> {
         instructions::auto_release::handler(ctx)
     }
 
@@ -52,11 +65,11 @@ pub mod agent_protocol {
 
     pub fn delegate_task(
         ctx: Context<DelegateTask>,
-        description: String,
+        title: String,
         delegation_amount: u64,
-        timestamp_seed: i64,
+        spec: Option<state::JobSpec>,
     ) -> Result<()> {
-        instructions::delegate_task::handler(ctx, description, delegation_amount, timestamp_seed)
+        instructions::delegate_task::handler(ctx, title, delegation_amount, spec)
     }
 
     pub fn raise_dispute(ctx: Context<RaiseDispute>) -> Result<()> {
@@ -70,4 +83,248 @@ pub mod agent_protocol {
     pub fn rate_agent(ctx: Context<RateAgent>, score: u8) -> Result<()> {
         instructions::rate_agent::handler(ctx, score)
     }
+
+    pub fn stake_agent(ctx: Context<StakeAgent>, amount: u64) -> Result<()> {
+        instructions::stake_agent::handler(ctx, amount)
+    }
+
+    pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+        instructions::request_unstake::handler(ctx, amount)
+    }
+
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        instructions::withdraw_stake::handler(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn post_bounty(
+        ctx: Context<PostBounty>,
+        title: String,
+        payment_lamports: u64,
+        required_capabilities: u16,
+        min_rating_x100: u16,
+        claim_window_seconds: Option<i64>,
+        auto_release_seconds: Option<i64>,
+        spec: Option<state::JobSpec>,
+    ) -> Result<()> {
+        instructions::post_bounty::handler(
+            ctx,
+            title,
+            payment_lamports,
+            required_capabilities,
+            min_rating_x100,
+            claim_window_seconds,
+            auto_release_seconds,
+            spec,
+        )
+    }
+
+    pub fn claim_bounty(ctx: Context<ClaimBounty>) -> Result<()> {
+        instructions::claim_bounty::handler(ctx)
+    }
+
+    pub fn create_job_request(
+        ctx: Context<CreateJobRequest>,
+        title: String,
+        max_budget_lamports: u64,
+        bidding_seconds: i64,
+        reveal_seconds: Option<i64>,
+        spec: Option<state::JobSpec>,
+    ) -> Result<()> {
+        instructions::create_job_request::handler(ctx, title, max_budget_lamports, bidding_seconds, reveal_seconds, spec)
+    }
+
+    pub fn submit_bid(
+        ctx: Context<SubmitBid>,
+        price_lamports: u64,
+        eta_seconds: i64,
+        proposal_uri: String,
+        commitment: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::submit_bid::handler(ctx, price_lamports, eta_seconds, proposal_uri, commitment)
+    }
+
+    pub fn reveal_bid(
+        ctx: Context<RevealBid>,
+        price_lamports: u64,
+        eta_seconds: i64,
+        proposal_uri: String,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::reveal_bid::handler(ctx, price_lamports, eta_seconds, proposal_uri, salt)
+    }
+
+    pub fn accept_bid<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptBid<'info>>,
+        auto_release_seconds: Option<i64>,
+    ) -> Result<()> {
+        instructions::accept_bid::handler(ctx, auto_release_seconds)
+    }
+
+    pub fn cancel_job_request(ctx: Context<CancelJobRequest>) -> Result<()> {
+        instructions::cancel_job_request::handler(ctx)
+    }
+
+    pub fn close_bid(ctx: Context<CloseBid>) -> Result<()> {
+        instructions::close_bid::handler(ctx)
+    }
+
+    pub fn create_subscription(
+        ctx: Context<CreateSubscription>,
+        amount_per_period: u64,
+        period_seconds: i64,
+        total_periods: u32,
+    ) -> Result<()> {
+        instructions::create_subscription::handler(ctx, amount_per_period, period_seconds, total_periods)
+    }
+
+    pub fn claim_period(ctx: Context<ClaimPeriod>) -> Result<()> {
+        instructions::claim_period::handler(ctx)
+    }
+
+    pub fn pause_subscription(ctx: Context<PauseSubscription>) -> Result<()> {
+        instructions::pause_subscription::handler(ctx)
+    }
+
+    pub fn resume_subscription(ctx: Context<ResumeSubscription>) -> Result<()> {
+        instructions::resume_subscription::handler(ctx)
+    }
+
+    pub fn cancel_subscription(ctx: Context<CancelSubscription>) -> Result<()> {
+        instructions::cancel_subscription::handler(ctx)
+    }
+
+    pub fn withdraw_stream(ctx: Context<WithdrawStream>) -> Result<()> {
+        instructions::withdraw_stream::handler(ctx)
+    }
+
+    pub fn stop_stream(ctx: Context<StopStream>) -> Result<()> {
+        instructions::stop_stream::handler(ctx)
+    }
+
+    pub fn set_encryption_key(ctx: Context<SetEncryptionKey>, encryption_key: Option<[u8; 32]>) -> Result<()> {
+        instructions::set_encryption_key::handler(ctx, encryption_key)
+    }
+
+    pub fn invoke_team<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InvokeTeam<'info>>,
+        title: String,
+        payment_lamports: u64,
+        auto_release_seconds: Option<i64>,
+        shares_bps: Vec<u16>,
+        require_all_signoff: bool,
+        spec: Option<state::JobSpec>,
+    ) -> Result<()> {
+        instructions::invoke_team::handler(
+            ctx,
+            title,
+            payment_lamports,
+            auto_release_seconds,
+            shares_bps,
+            require_all_signoff,
+            spec,
+        )
+    }
+
+    pub fn sign_off_result(ctx: Context<SignOffResult>) -> Result<()> {
+        instructions::sign_off_result::handler(ctx)
+    }
+
+    pub fn invoke_agents_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InvokeAgentsBatch<'info>>,
+        entries: Vec<state::BatchEntry>,
+        auto_release_seconds: Option<i64>,
+    ) -> Result<()> {
+        instructions::invoke_agents_batch::handler(ctx, entries, auto_release_seconds)
+    }
+
+    pub fn propose_partial_release(
+        ctx: Context<ProposePartialRelease>,
+        agent_lamports: u64,
+    ) -> Result<()> {
+        instructions::propose_partial_release::handler(ctx, agent_lamports)
+    }
+
+    pub fn accept_partial_release<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptPartialRelease<'info>>,
+        agent_lamports: u64,
+    ) -> Result<()> {
+        instructions::accept_partial_release::handler(ctx, agent_lamports)
+    }
+
+    pub fn reassign_job(
+        ctx: Context<ReassignJob>,
+        stake_requirement: Option<state::StakeRequirement>,
+    ) -> Result<()> {
+        instructions::reassign_job::handler(ctx, stake_requirement)
+    }
+
+    pub fn cancel_job_tree<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelJobTree<'info>>,
+    ) -> Result<()> {
+        instructions::cancel_job_tree::handler(ctx)
+    }
+
+    pub fn set_max_concurrent_jobs(
+        ctx: Context<SetMaxConcurrentJobs>,
+        max_concurrent_jobs: u16,
+    ) -> Result<()> {
+        instructions::set_max_concurrent_jobs::handler(ctx, max_concurrent_jobs)
+    }
+
+    pub fn set_price_table(
+        ctx: Context<SetPriceTable>,
+        price_table: Vec<state::CapabilityPrice>,
+    ) -> Result<()> {
+        instructions::set_price_table::handler(ctx, price_table)
+    }
+
+    pub fn set_agent_keys(
+        ctx: Context<SetAgentKeys>,
+        payout: Pubkey,
+        operator: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_agent_keys::handler(ctx, payout, operator)
+    }
+
+    pub fn propose_agent_transfer(
+        ctx: Context<ProposeAgentTransfer>,
+        new_owner: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::propose_agent_transfer::handler(ctx, new_owner)
+    }
+
+    pub fn accept_agent_transfer(ctx: Context<AcceptAgentTransfer>) -> Result<()> {
+        instructions::accept_agent_transfer::handler(ctx)
+    }
+
+    pub fn set_agent_metadata(
+        ctx: Context<SetAgentMetadata>,
+        endpoint_uri: String,
+        metadata_uri: String,
+        metadata_hash: [u8; 32],
+        input_formats: u16,
+        schema_version: u8,
+    ) -> Result<()> {
+        instructions::set_agent_metadata::handler(
+            ctx,
+            endpoint_uri,
+            metadata_uri,
+            metadata_hash,
+            input_formats,
+            schema_version,
+        )
+    }
+
+    pub fn heartbeat(ctx: Context<Heartbeat>) -> Result<()> {
+        instructions::heartbeat::handler(ctx)
+    }
+
+    pub fn set_access_list(
+        ctx: Context<SetAccessList>,
+        mode: state::AccessMode,
+        clients: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_access_list::handler(ctx, mode, clients)
+    }
 }
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::AgentProtocolError;
+
+/// `amount * bps / 10_000`, computed in u128 to avoid intermediate overflow
+pub fn apply_bps(amount: u64, bps: u16) -> Result<u64> {
+    let scaled = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(AgentProtocolError::Overflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(scaled).map_err(|_| AgentProtocolError::Overflow.into())
+}
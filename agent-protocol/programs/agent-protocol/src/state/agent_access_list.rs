@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::error::AgentProtocolError;
+
+/// Which clients an agent takes work from
+#[account]
+#[derive(InitSpace)]
+pub struct AgentAccessList {
+    pub agent_profile: Pubkey,
+    pub mode: AccessMode,
+    /// Client keys the mode applies to; max_len matches MAX_ACCESS_LIST_ENTRIES
+    #[max_len(16)]
+    pub clients: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AccessMode {
+    /// Anyone may hire the agent; `clients` is ignored
+    Open,
+    /// Only listed clients may hire the agent
+    Allowlist,
+    /// Everyone except listed clients may hire the agent
+    Blocklist,
+}
+
+impl AgentAccessList {
+    pub fn permits(&self, client: &Pubkey) -> bool {
+        match self.mode {
+            AccessMode::Open => true,
+            AccessMode::Allowlist => self.clients.contains(client),
+            AccessMode::Blocklist => !self.clients.contains(client),
+        }
+    }
+
+    /// Enforce the list stored at `info`, the agent's access PDA. An agent
+    /// that never created one takes work from anyone.
+    pub fn check(info: &AccountInfo, client: &Pubkey) -> Result<()> {
+        if info.owner == &crate::ID && !info.data_is_empty() {
+            let list = AgentAccessList::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            require!(list.permits(client), AgentProtocolError::ClientNotPermitted);
+        }
+        Ok(())
+    }
+
+    /// `check` for an access PDA passed in remaining accounts, where Anchor
+    /// has not already tied it to `agent_profile`
+    pub fn check_for(agent_profile: &Pubkey, info: &AccountInfo, client: &Pubkey) -> Result<()> {
+        let (expected, _) =
+            Pubkey::find_program_address(&[b"access", agent_profile.as_ref()], &crate::ID);
+        require_keys_eq!(info.key(), expected, AgentProtocolError::AccessListMismatch);
+        Self::check(info, client)
+    }
+}
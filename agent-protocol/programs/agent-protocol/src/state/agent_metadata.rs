@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::constants::METADATA_SCHEMA_VERSION;
+use crate::error::AgentProtocolError;
+
+/// How to reach an agent, kept beside its profile so the profile stays small
+#[account]
+#[derive(InitSpace)]
+pub struct AgentMetadata {
+    pub agent_profile: Pubkey,
+    /// Service endpoint orchestrators call to hand the agent work
+    #[max_len(128)]
+    pub endpoint_uri: String,
+    /// Off-chain metadata JSON; empty when the agent publishes none
+    #[max_len(128)]
+    pub metadata_uri: String,
+    /// SHA-256 of the metadata JSON
+    pub metadata_hash: [u8; 32],
+    /// FORMAT_* bits for the job inputs the agent accepts
+    pub input_formats: u16,
+    /// Version of the metadata JSON schema `metadata_uri` follows
+    pub schema_version: u8,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl AgentMetadata {
+    pub fn validate(
+        endpoint_uri: &str,
+        metadata_uri: &str,
+        metadata_hash: &[u8; 32],
+        schema_version: u8,
+    ) -> Result<()> {
+        require!(
+            endpoint_uri.len() <= 128 && metadata_uri.len() <= 128,
+            AgentProtocolError::UriTooLong
+        );
+        require!(
+            !endpoint_uri.is_empty()
+                && (metadata_uri.is_empty() || *metadata_hash != [0u8; 32])
+                && (1..=METADATA_SCHEMA_VERSION).contains(&schema_version),
+            AgentProtocolError::InvalidAgentMetadata
+        );
+        Ok(())
+    }
+}
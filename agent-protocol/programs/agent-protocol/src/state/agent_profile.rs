@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use crate::constants::MAX_PRICE_TABLE_ENTRIES;
+use crate::error::AgentProtocolError;
 
 #[account]
 #[derive(InitSpace)]
@@ -15,5 +17,135 @@ pub struct AgentProfile {
     pub rating_count: u32,
     pub jobs_completed: u32,
     pub created_at: i64,
+    /// X25519 public key for encrypted result delivery
+    pub encryption_key: Option<[u8; 32]>,
+    /// Total lamports paid to this agent, bonuses included
+    pub lifetime_earnings: u64,
+    /// Owner-set cap on jobs in flight at once (0 = unlimited)
+    pub max_concurrent_jobs: u16,
+    /// Jobs assigned to this agent that have not reached a terminal state
+    pub active_jobs: u16,
+    /// Per-capability prices overriding `price_lamports`; max_len matches
+    /// MAX_PRICE_TABLE_ENTRIES
+    #[max_len(8)]
+    pub price_table: Vec<CapabilityPrice>,
+    /// Wallet that receives this agent's payouts; the owner until changed
+    pub payout: Pubkey,
+    /// Hot key that may deliver, delegate and dispute on the owner's behalf
+    pub operator: Option<Pubkey>,
+    /// Key the profile was registered under: seeds the PDA and identifies the
+    /// agent on jobs, bids and subscriptions. Unlike `owner`, it never changes.
+    pub agent_id: Pubkey,
+    /// New owner named by propose_agent_transfer, until accepted
+    pub pending_owner: Option<Pubkey>,
+    /// Last heartbeat from the owner or operator; registration counts as one
+    pub last_seen_at: Option<i64>,
+    /// Client-raised disputes still open against this agent; stake cannot be
+    /// withdrawn while any could slash it
+    pub open_disputes: u16,
     pub bump: u8,
 }
+
+/// Price for one capability, optionally for one size tier of it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct CapabilityPrice {
+    /// A single CAP_* bit the agent offers
+    pub capability: u16,
+    /// Size tier this price applies to; 0 prices every tier without its own entry
+    pub tier: u8,
+    pub price_lamports: u64,
+}
+
+impl AgentProfile {
+    /// Average rating * 100 (avoids f64); 0 when unrated
+    pub fn rating_avg_x100(&self) -> u64 {
+        if self.rating_count == 0 {
+            return 0;
+        }
+        self.rating_sum.saturating_mul(100) / self.rating_count as u64
+    }
+
+    /// Whether `key` may act as this agent on its jobs: the owner or its operator
+    pub fn can_operate(&self, key: Pubkey) -> bool {
+        key == self.owner || self.operator == Some(key)
+    }
+
+    /// Whether the agent sent a heartbeat within `max_age` seconds of `now`
+    pub fn is_live(&self, now: i64, max_age: i64) -> bool {
+        self.last_seen_at
+            .is_some_and(|seen| now.saturating_sub(seen) <= max_age)
+    }
+
+    /// Reject malformed tables before they are stored
+    pub fn validate_price_table(&self, table: &[CapabilityPrice]) -> Result<()> {
+        require!(
+            table.len() <= MAX_PRICE_TABLE_ENTRIES,
+            AgentProtocolError::InvalidPriceTable
+        );
+        for (i, entry) in table.iter().enumerate() {
+            require!(
+                entry.capability.is_power_of_two()
+                    && self.capabilities & entry.capability == entry.capability
+                    && entry.price_lamports > 0,
+                AgentProtocolError::InvalidPriceTable
+            );
+            require!(
+                table[..i]
+                    .iter()
+                    .all(|e| (e.capability, e.tier) != (entry.capability, entry.tier)),
+                AgentProtocolError::InvalidPriceTable
+            );
+        }
+        Ok(())
+    }
+
+    /// Floor price for a job needing `capabilities` at size `tier`. Each
+    /// requested capability costs its tier entry, else its tier-0 entry, else
+    /// `price_lamports`; the job costs the highest of those.
+    pub fn price_for(&self, capabilities: u16, tier: u8) -> u64 {
+        let lookup = |capability: u16, tier: u8| {
+            self.price_table
+                .iter()
+                .find(|e| e.capability == capability && e.tier == tier)
+                .map(|e| e.price_lamports)
+        };
+        (0..u16::BITS)
+            .map(|bit| 1u16 << bit)
+            .filter(|&capability| capabilities & capability != 0)
+            .map(|capability| {
+                lookup(capability, tier)
+                    .or_else(|| lookup(capability, 0))
+                    .unwrap_or(self.price_lamports)
+            })
+            .max()
+            .unwrap_or(self.price_lamports)
+    }
+
+    /// Take a concurrency slot for a newly assigned job
+    pub fn open_job(&mut self) -> Result<()> {
+        require!(
+            self.max_concurrent_jobs == 0 || self.active_jobs < self.max_concurrent_jobs,
+            AgentProtocolError::AgentAtCapacity
+        );
+        self.active_jobs = self.active_jobs
+            .checked_add(1)
+            .ok_or(AgentProtocolError::Overflow)?;
+        Ok(())
+    }
+
+    /// Free the slot held by a job that reached a terminal state
+    pub fn close_job(&mut self) -> Result<()> {
+        self.active_jobs = self.active_jobs
+            .checked_sub(1)
+            .ok_or(AgentProtocolError::Overflow)?;
+        Ok(())
+    }
+
+    /// Add a payout to the lifetime earnings counter
+    pub fn record_earnings(&mut self, lamports: u64) -> Result<()> {
+        self.lifetime_earnings = self.lifetime_earnings
+            .checked_add(lamports)
+            .ok_or(AgentProtocolError::Overflow)?;
+        Ok(())
+    }
+}
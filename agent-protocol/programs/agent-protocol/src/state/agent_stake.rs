@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::constants::MAX_SLASH_BPS;
+use crate::error::AgentProtocolError;
+
+#[account]
+#[derive(InitSpace)]
+pub struct AgentStake {
+    pub agent_profile: Pubkey,
+    /// Lamports backing the agent, slashable on a lost dispute
+    pub staked_lamports: u64,
+    /// Lamports queued for withdrawal, still slashable until withdrawn
+    pub unstaking_lamports: u64,
+    pub unstake_available_at: Option<i64>,
+    pub bump: u8,
+}
+
+/// Client collateral terms for invoke_agent
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct StakeRequirement {
+    /// Active stake the agent must hold when the job opens
+    pub min_lamports: u64,
+    /// Share of the agent's stake slashed to the client if it loses a dispute
+    /// the client raised (basis points, at most MAX_SLASH_BPS)
+    pub slash_bps: u16,
+}
+
+impl StakeRequirement {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.slash_bps <= MAX_SLASH_BPS, AgentProtocolError::InvalidSlashBps);
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Bid {
+    pub job_request: Pubkey,
+    /// Wallet that placed the bid (the profile's owner at the time); reclaims its rent
+    pub agent: Pubkey,
+    pub agent_profile: Pubkey,
+    pub price_lamports: u64,
+    pub eta_seconds: i64,
+    #[max_len(128)]
+    pub proposal_uri: String,
+    /// sha256(price || eta || proposal_uri || salt) for sealed bids
+    pub commitment: Option<[u8; 32]>,
+    /// Open bids are revealed on submission
+    pub revealed: bool,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Bid {
+    pub fn commitment_hash(
+        price_lamports: u64,
+        eta_seconds: i64,
+        proposal_uri: &str,
+        salt: &[u8; 32],
+    ) -> [u8; 32] {
+        solana_sha256_hasher::hashv(&[
+            &price_lamports.to_le_bytes(),
+            &eta_seconds.to_le_bytes(),
+            proposal_uri.as_bytes(),
+            salt,
+        ])
+        .to_bytes()
+    }
+}
@@ -1,26 +1,124 @@
 use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+use crate::constants::{BPS_DENOMINATOR, MAX_DELEGATION_ALLOWLIST};
+use crate::error::AgentProtocolError;
+use crate::math::apply_bps;
+use super::{AgentProfile, JobExtension, StakeRequirement};
 
 #[account]
 #[derive(InitSpace)]
 pub struct Job {
     pub client: Pubkey,
+    /// Assigned agent's `agent_id`, which survives profile ownership transfers
     pub agent: Pubkey,
     pub escrow_lamports: u64,
     pub status: JobStatus,
-    #[max_len(256)]
-    pub description: String,
+    /// Short human-readable title; the full brief lives off-chain in `spec`
+    #[max_len(64)]
+    pub title: String,
+    /// Off-chain job spec pinned by hash
+    pub spec: Option<JobSpec>,
     #[max_len(128)]
     pub result_uri: String,
+    /// SHA-256 of the deliverable; committed ahead of the URI in commit-reveal mode
+    pub result_hash: Option<[u8; 32]>,
     pub parent_job: Option<Pubkey>,
     pub active_children: u8,
     pub auto_release_at: Option<i64>,
     pub disputed_at: Option<i64>,
     pub created_at: i64,
     pub completed_at: Option<i64>,
-    pub timestamp_seed: i64,
+    /// Creator's JobCounter nonce used in the PDA seeds
+    pub nonce: u64,
+    /// Capability bits the assigned agent must hold (0 = no requirement)
+    pub required_capabilities: u16,
+    /// Minimum average rating (x100) a bounty claimant must hold
+    pub min_rating_x100: u16,
+    /// Open bounties can no longer be claimed after this time
+    pub claim_deadline: Option<i64>,
+    /// Number of agents on a team job (0 = single-agent job)
+    pub team_size: u8,
+    /// Team sign-offs still needed before the lead's delivery completes the job
+    pub pending_signoffs: u8,
+    /// Jobs opened together by invoke_agents_batch share this ID (the first job's address)
+    pub group_id: Option<Pubkey>,
+    /// Tip the client attached at release_payment, paid on top of the escrow
+    pub bonus_lamports: u64,
+    /// Agent's share under the client's pending partial-release proposal; the
+    /// rest of the escrow refunds the client if the agent accepts
+    pub partial_release_lamports: Option<u64>,
+    /// Escrow at creation, before any delegation
+    pub original_escrow: u64,
+    /// Lamports out with child jobs, less whatever unfinished children returned
+    pub delegated_total: u64,
+    /// Running hash over finalized children: sha256(prev || child || result_hash),
+    /// starting from zero bytes
+    pub child_results_hash: [u8; 32],
+    /// Children folded into `child_results_hash`
+    pub children_completed: u32,
+    /// Size tier the job was priced at against the agent's price table
+    pub price_tier: u8,
+    /// Who raised the open dispute; only a client-raised dispute slashes stake
+    pub disputed_by: Option<Pubkey>,
+    /// Share of the agent's stake slashed if the client wins a dispute (basis points)
+    pub slash_bps: u16,
+    /// Open bounties only: auto-release delay in seconds, started when an agent claims
+    pub auto_release_delay: Option<i64>,
+    /// Stream, encryption and delegation options live in a JobExtension PDA
+    /// (`["job_ext", job]`), which exists only when this is set
+    pub has_extension: bool,
     pub bump: u8,
 }
 
+impl Job {
+    /// What the delegating agent keeps after paying sub-agents: its own
+    /// `lead_amount`, after any other team members were paid. None unless the
+    /// job delegated.
+    pub fn delegation_margin(&self, lead_amount: u64) -> Option<u64> {
+        (self.delegated_total > 0).then_some(lead_amount)
+    }
+
+    /// Fold a finalized child job's result into the running hash
+    pub fn record_child_result(&mut self, child: Pubkey, result_hash: [u8; 32]) -> Result<()> {
+        self.child_results_hash =
+            hashv(&[&self.child_results_hash, child.as_ref(), &result_hash]).to_bytes();
+        self.children_completed = self.children_completed
+            .checked_add(1)
+            .ok_or(AgentProtocolError::Overflow)?;
+        Ok(())
+    }
+
+    /// Take back an unfinished child's escrow, undoing its delegation. The
+    /// caller moves the lamports.
+    pub fn reclaim_child(&mut self, escrow: u64) -> Result<()> {
+        self.active_children = self.active_children
+            .checked_sub(1)
+            .ok_or(AgentProtocolError::Overflow)?;
+        self.return_child_escrow(escrow)
+    }
+
+    /// Credit back escrow a child job did not spend. The caller moves the lamports.
+    pub fn return_child_escrow(&mut self, escrow: u64) -> Result<()> {
+        self.escrow_lamports = self.escrow_lamports
+            .checked_add(escrow)
+            .ok_or(AgentProtocolError::Overflow)?;
+        self.delegated_total = self.delegated_total
+            .checked_sub(escrow)
+            .ok_or(AgentProtocolError::Overflow)?;
+        Ok(())
+    }
+
+    /// Check that the extension PDA was passed exactly when the job has one.
+    /// Its seeds already bind it to this job.
+    pub fn check_extension(&self, extension: Option<&JobExtension>) -> Result<()> {
+        require!(
+            self.has_extension == extension.is_some(),
+            AgentProtocolError::ExtensionMismatch
+        );
+        Ok(())
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
 pub enum JobStatus {
     Pending,
@@ -29,4 +127,147 @@ pub enum JobStatus {
     Disputed,
     Cancelled,
     Finalized,
+    /// Open bounty escrowed without an assigned agent
+    Open,
+}
+
+/// Off-chain job specification: the agent fetches `uri` and checks it hashes to `hash`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct JobSpec {
+    #[max_len(128)]
+    pub uri: String,
+    /// SHA-256 of the spec document
+    pub hash: [u8; 32],
+}
+
+/// Jobs need a title, a spec, or both
+pub fn validate_title_and_spec(title: &str, spec: Option<&JobSpec>) -> Result<()> {
+    require!(!title.is_empty() || spec.is_some(), AgentProtocolError::EmptyDescription);
+    require!(title.len() <= 64, AgentProtocolError::TitleTooLong);
+    if let Some(spec) = spec {
+        require!(
+            !spec.uri.is_empty() && spec.hash != [0u8; 32],
+            AgentProtocolError::InvalidSpec
+        );
+        require!(spec.uri.len() <= 128, AgentProtocolError::UriTooLong);
+    }
+    Ok(())
+}
+
+/// How the agent on a job may subcontract it, set by the client at invoke_agent
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct DelegationPolicy {
+    /// When false, delegate_task is rejected outright
+    pub allow_delegation: bool,
+    /// Share of the escrow all child jobs may take together
+    pub max_share_bps: u16,
+    /// Sub-agent profiles the agent may hire; empty allows any. max_len
+    /// matches MAX_DELEGATION_ALLOWLIST
+    #[max_len(4)]
+    pub allowed_sub_agents: Vec<Pubkey>,
+    /// Minimum sub-agent `rating_avg_x100` (0 = no minimum)
+    pub min_sub_agent_rating_x100: u16,
+}
+
+impl DelegationPolicy {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.max_share_bps <= BPS_DENOMINATOR
+                && self.allowed_sub_agents.len() <= MAX_DELEGATION_ALLOWLIST,
+            AgentProtocolError::InvalidDelegationPolicy
+        );
+        Ok(())
+    }
+
+    /// Check one delegation of `amount` to `sub_agent`. `escrow_total` is the
+    /// job's original escrow, `delegated_total` what is already out.
+    pub fn authorize(
+        &self,
+        sub_agent: &Account<AgentProfile>,
+        amount: u64,
+        escrow_total: u64,
+        delegated_total: u64,
+    ) -> Result<()> {
+        require!(self.allow_delegation, AgentProtocolError::DelegationNotAllowed);
+        require!(
+            self.allowed_sub_agents.is_empty()
+                || self.allowed_sub_agents.contains(&sub_agent.key()),
+            AgentProtocolError::SubAgentNotAllowed
+        );
+        require!(
+            sub_agent.rating_avg_x100() >= self.min_sub_agent_rating_x100 as u64,
+            AgentProtocolError::SubAgentRatingTooLow
+        );
+        let delegated = delegated_total
+            .checked_add(amount)
+            .ok_or(AgentProtocolError::Overflow)?;
+        require!(
+            delegated <= apply_bps(escrow_total, self.max_share_bps)?,
+            AgentProtocolError::DelegationShareExceeded
+        );
+        Ok(())
+    }
+}
+
+/// One job in an invoke_agents_batch call
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct BatchEntry {
+    pub title: String,
+    pub payment_lamports: u64,
+    pub spec: Option<JobSpec>,
+}
+
+/// invoke_agent's optional terms; the default is a plain job at the agent's
+/// default price with no auto-release
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct InvokeOptions {
+    pub auto_release_seconds: Option<i64>,
+    /// Minimum agent stake, and the share slashed if the client wins a dispute
+    pub stake_requirement: Option<StakeRequirement>,
+    /// Pay by time instead of on delivery; excludes auto-release
+    pub stream: Option<StreamWindow>,
+    /// Client X25519 key; results must then be delivered encrypted
+    pub client_encryption_key: Option<[u8; 32]>,
+    pub spec: Option<JobSpec>,
+    pub delegation_policy: Option<DelegationPolicy>,
+    /// Capabilities the agent must have; they also select its price
+    pub required_capabilities: u16,
+    pub price_tier: u8,
+    /// Refuse agents whose last heartbeat is older than this many seconds
+    pub max_heartbeat_age: Option<i64>,
+}
+
+/// Client-chosen vesting window passed to invoke_agent
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct StreamWindow {
+    pub start_at: i64,
+    pub end_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct StreamSchedule {
+    pub start_at: i64,
+    pub end_at: i64,
+    pub total_lamports: u64,
+    pub withdrawn_lamports: u64,
+}
+
+impl StreamSchedule {
+    /// Lamports vested at `now`, linear between start_at and end_at
+    pub fn vested(&self, now: i64) -> u64 {
+        if now <= self.start_at {
+            return 0;
+        }
+        if now >= self.end_at {
+            return self.total_lamports;
+        }
+        let elapsed = (now - self.start_at) as u128;
+        let duration = (self.end_at - self.start_at) as u128;
+        (self.total_lamports as u128 * elapsed / duration) as u64
+    }
+
+    /// Vested lamports the agent has not withdrawn yet
+    pub fn withdrawable(&self, now: i64) -> u64 {
+        self.vested(now).saturating_sub(self.withdrawn_lamports)
+    }
 }
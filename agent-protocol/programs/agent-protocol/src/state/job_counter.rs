@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::error::AgentProtocolError;
+
+/// Per-creator nonce. Every job a wallet creates (as client or as a
+/// delegating agent) is seeded `["job", creator, nonce]`, so jobs 0..next_nonce
+/// can be enumerated without an indexer. Job requests and subscriptions draw
+/// from the same nonce, so some of those addresses hold no job.
+#[account]
+#[derive(InitSpace)]
+pub struct JobCounter {
+    pub authority: Pubkey,
+    pub next_nonce: u64,
+    pub bump: u8,
+}
+
+impl JobCounter {
+    /// Claim the next nonce; initializes the counter on first use
+    pub fn take_nonce(&mut self, authority: Pubkey, bump: u8) -> Result<u64> {
+        self.authority = authority;
+        self.bump = bump;
+        let nonce = self.next_nonce;
+        self.next_nonce = nonce.checked_add(1).ok_or(AgentProtocolError::Overflow)?;
+        Ok(nonce)
+    }
+
+    /// Address of the counter PDA for `authority`
+    pub fn address(authority: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"job_counter", authority.as_ref()], &crate::ID).0
+    }
+
+    /// Address of the job `authority` created with `nonce`
+    pub fn job_address(authority: &Pubkey, nonce: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"job", authority.as_ref(), &nonce.to_le_bytes()],
+            &crate::ID,
+        )
+        .0
+    }
+
+    /// Every job PDA this counter could have created, oldest first; nonces
+    /// spent on job requests or subscriptions yield addresses with no account
+    pub fn job_addresses(&self) -> impl Iterator<Item = Pubkey> + '_ {
+        (0..self.next_nonce).map(move |nonce| Self::job_address(&self.authority, nonce))
+    }
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use crate::envelope::ResultEnvelope;
+use super::{DelegationPolicy, StreamSchedule};
+
+/// Options few jobs use, kept beside the job so plain jobs stay small. Only
+/// exists when the job's `has_extension` is set; closed with the job.
+#[account]
+#[derive(InitSpace)]
+pub struct JobExtension {
+    pub job: Pubkey,
+    /// Linear vesting schedule for pay-per-time jobs
+    pub stream: Option<StreamSchedule>,
+    /// Client X25519 public key; when set, results must be delivered encrypted
+    pub client_encryption_key: Option<[u8; 32]>,
+    /// Wrapped content key for an encrypted result
+    pub result_envelope: Option<ResultEnvelope>,
+    /// Client limits on delegate_task; None leaves delegation unrestricted
+    pub delegation_policy: Option<DelegationPolicy>,
+    pub bump: u8,
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+use super::JobSpec;
+
+#[account]
+#[derive(InitSpace)]
+pub struct JobRequest {
+    pub client: Pubkey,
+    #[max_len(64)]
+    pub title: String,
+    pub spec: Option<JobSpec>,
+    /// Bids above this price are rejected
+    pub max_budget_lamports: u64,
+    /// Sealed requests take bid commitments and reveal them after bidding closes
+    pub sealed: bool,
+    pub bid_deadline: i64,
+    /// End of the reveal window (sealed requests only)
+    pub reveal_deadline: Option<i64>,
+    pub bid_count: u32,
+    pub created_at: i64,
+    /// Creator's JobCounter nonce used in the PDA seeds
+    pub nonce: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// Roster and payout split for a multi-agent job. The lead (first member) is
+/// the job's `agent`: it delivers the result and can delegate like any agent.
+#[account]
+#[derive(InitSpace)]
+pub struct JobTeam {
+    pub job: Pubkey,
+    /// Lead first; max_len matches MAX_TEAM_MEMBERS
+    #[max_len(5)]
+    pub members: Vec<TeamMember>,
+    /// When set, every member must sign off on the lead's delivery before the
+    /// job completes; otherwise the lead's update_job completes it alone
+    pub require_all_signoff: bool,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct TeamMember {
+    pub agent_profile: Pubkey,
+    /// The profile's `agent_id`; the member's share goes to its profile's
+    /// payout wallet
+    pub agent: Pubkey,
+    pub share_bps: u16,
+    pub signed_off: bool,
+}
@@ -1,7 +1,25 @@
+pub mod agent_access_list;
+pub mod agent_metadata;
 pub mod agent_profile;
+pub mod agent_stake;
+pub mod bid;
 pub mod job;
+pub mod job_counter;
+pub mod job_extension;
+pub mod job_request;
+pub mod job_team;
 pub mod rating;
+pub mod subscription;
 
+pub use agent_access_list::*;
+pub use agent_metadata::*;
 pub use agent_profile::*;
+pub use agent_stake::*;
+pub use bid::*;
 pub use job::*;
+pub use job_counter::*;
+pub use job_extension::*;
+pub use job_request::*;
+pub use job_team::*;
 pub use rating::*;
+pub use subscription::*;
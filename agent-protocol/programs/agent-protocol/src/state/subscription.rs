@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Subscription {
+    pub client: Pubkey,
+    pub agent: Pubkey,
+    pub agent_profile: Pubkey,
+    pub amount_per_period: u64,
+    pub period_seconds: i64,
+    pub total_periods: u32,
+    pub claimed_periods: u32,
+    /// Time at which the next unclaimed period becomes claimable
+    pub next_claim_at: i64,
+    /// Accrual stops while paused; resuming shifts next_claim_at by the pause length
+    pub paused_at: Option<i64>,
+    pub created_at: i64,
+    /// Creator's JobCounter nonce used in the PDA seeds
+    pub nonce: u64,
+    pub bump: u8,
+}
+
+impl Subscription {
+    /// Periods that have elapsed but not been claimed as of `now`
+    pub fn claimable_periods(&self, now: i64) -> u32 {
+        let effective_now = self.paused_at.unwrap_or(now);
+        if effective_now < self.next_claim_at {
+            return 0;
+        }
+        let remaining = self.total_periods.saturating_sub(self.claimed_periods);
+        let elapsed = (effective_now - self.next_claim_at) / self.period_seconds + 1;
+        u32::try_from(elapsed).unwrap_or(u32::MAX).min(remaining)
+    }
+}
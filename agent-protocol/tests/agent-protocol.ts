@@ -9,6 +9,15 @@ import {
   LAMPORTS_PER_SOL,
 } from "@solana/web3.js";
 import BN from "bn.js";
+import {
+  createCipheriv,
+  createDecipheriv,
+  createHash,
+  diffieHellman,
+  generateKeyPairSync,
+  KeyObject,
+  randomBytes,
+} from "crypto";
 
 describe("agent-protocol", () => {
   const provider = anchor.AnchorProvider.env();
@@ -24,14 +33,65 @@ describe("agent-protocol", () => {
       program.programId
     );
 
-  const getJobPDA = (client: PublicKey, agentProfile: PublicKey, ts: BN) =>
+  const getJobCounterPDA = (authority: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("job_counter"), authority.toBuffer()],
+      program.programId
+    );
+
+  const getJobPDA = (creator: PublicKey, nonce: BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("job"), creator.toBuffer(), nonce.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+  /** Nonce the creator's next job will use (0 before their first job) */
+  const nextJobNonce = async (creator: PublicKey) => {
+    const counter = await program.account.jobCounter.fetchNullable(getJobCounterPDA(creator)[0]);
+    return counter ? counter.nextNonce : new BN(0);
+  };
+
+  const nextJobPDA = async (creator: PublicKey) => getJobPDA(creator, await nextJobNonce(creator))[0];
+
+  /** Every job the creator has opened, oldest first */
+  const listJobPDAs = async (creator: PublicKey) => {
+    const count = (await nextJobNonce(creator)).toNumber();
+    return Array.from({ length: count }, (_, i) => getJobPDA(creator, new BN(i))[0]);
+  };
+
+  const getStakePDA = (agentProfile: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), agentProfile.toBuffer()],
+      program.programId
+    );
+
+  const getJobRequestPDA = (client: PublicKey, nonce: BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("job_request"), client.toBuffer(), nonce.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+  const getBidPDA = (jobRequest: PublicKey, agentProfile: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("bid"), jobRequest.toBuffer(), agentProfile.toBuffer()],
+      program.programId
+    );
+
+  const getSubscriptionPDA = (client: PublicKey, nonce: BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("subscription"), client.toBuffer(), nonce.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+  const getTeamPDA = (job: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("team"), job.toBuffer()],
+      program.programId
+    );
+
+  const getJobExtensionPDA = (job: PublicKey) =>
     PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("job"),
-        client.toBuffer(),
-        agentProfile.toBuffer(),
-        ts.toArrayLike(Buffer, "le", 8),
-      ],
+      [Buffer.from("job_ext"), job.toBuffer()],
       program.programId
     );
 
@@ -41,6 +101,12 @@ describe("agent-protocol", () => {
       program.programId
     );
 
+  const getAccessListPDA = (agentProfile: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("access"), agentProfile.toBuffer()],
+      program.programId
+    );
+
   // ─── Utility ───
 
   const airdrop = async (pubkey: PublicKey, amount = 10 * LAMPORTS_PER_SOL) => {
@@ -50,14 +116,60 @@ describe("agent-protocol", () => {
 
   const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
 
-  let tsCounter = Math.floor(Date.now() / 1000);
-  const nextTs = () => new BN(tsCounter++);
-
   const PRICE = new BN(LAMPORTS_PER_SOL / 2); // 0.5 SOL
   const PAYMENT = new BN(LAMPORTS_PER_SOL); // 1 SOL
 
   // ─── Reusable action helpers ───
 
+  type InvokeOpts = {
+    stakeRequirement?: { minLamports: BN; slashBps: number };
+    stream?: { startAt: BN; endAt: BN };
+    clientEncryptionKey?: number[];
+    spec?: JobSpec;
+    delegationPolicy?: DelegationPolicy;
+    capabilities?: number;
+    priceTier?: number;
+    maxHeartbeatAge?: BN;
+  };
+
+  /** invoke_agent's InvokeOptions argument; no opts gives a plain job */
+  const invokeOptions = (opts: InvokeOpts = {}, autoReleaseSecs: BN | null = null) => ({
+    autoReleaseSeconds: autoReleaseSecs,
+    stakeRequirement: opts.stakeRequirement ?? null,
+    stream: opts.stream ?? null,
+    clientEncryptionKey: opts.clientEncryptionKey ?? null,
+    spec: opts.spec ?? null,
+    delegationPolicy: opts.delegationPolicy ?? null,
+    requiredCapabilities: opts.capabilities ?? 0,
+    priceTier: opts.priceTier ?? 0,
+    maxHeartbeatAge: opts.maxHeartbeatAge ?? null,
+  });
+
+  type DelegationPolicy = {
+    allowDelegation: boolean;
+    maxShareBps: number;
+    allowedSubAgents: PublicKey[];
+    minSubAgentRatingX100: number;
+  };
+
+  type JobSpec = { uri: string; hash: number[] };
+
+  /** Team roster PDA plus the non-lead members, in roster order */
+  type TeamPayout = { team: PublicKey; members: { profile: PublicKey; wallet: PublicKey }[] };
+
+  const teamRemainingAccounts = (payout: TeamPayout | null) =>
+    (payout?.members ?? []).flatMap(({ profile, wallet }) => [
+      { pubkey: profile, isWritable: true, isSigner: false },
+      { pubkey: wallet, isWritable: true, isSigner: false },
+    ]);
+
+  type ResultEnvelope = {
+    version: number;
+    agentKey: number[];
+    nonce: number[];
+    wrappedKey: number[];
+  };
+
   async function registerAgent(
     owner: Keypair,
     name = "TestAgent",
@@ -77,25 +189,45 @@ describe("agent-protocol", () => {
     agentProfilePDA: PublicKey,
     payment = PAYMENT,
     autoReleaseSecs: BN | null = null,
-    desc = "Test task"
+    desc = "Test task",
+    opts: InvokeOpts = {}
   ) {
-    const ts = nextTs();
-    const [jobPDA] = getJobPDA(client.publicKey, agentProfilePDA, ts);
+    const nonce = await nextJobNonce(client.publicKey);
+    const [jobPDA] = getJobPDA(client.publicKey, nonce);
+    const stakeRequirement = opts.stakeRequirement ?? null;
+    const hasExtension = !!(opts.stream || opts.clientEncryptionKey || opts.delegationPolicy);
     await program.methods
-      .invokeAgent(desc, payment, autoReleaseSecs, ts)
+      .invokeAgent(desc, payment, invokeOptions(opts, autoReleaseSecs))
       .accountsPartial({
         client: client.publicKey,
         agentProfile: agentProfilePDA,
+        job: jobPDA,
+        jobExtension: hasExtension ? getJobExtensionPDA(jobPDA)[0] : null,
+        agentStake: stakeRequirement ? getStakePDA(agentProfilePDA)[0] : null,
       })
       .signers([client])
       .rpc();
-    return { jobPDA, ts };
+    return { jobPDA, nonce };
   }
 
-  async function updateJob(agent: Keypair, jobPDA: PublicKey, uri = "https://result.example.com") {
+  const sha256 = (data: string | Buffer) =>
+    Array.from(createHash("sha256").update(data).digest());
+
+  async function updateJob(
+    agent: Keypair,
+    jobPDA: PublicKey,
+    uri = "https://result.example.com",
+    hash = sha256(uri),
+    envelope: ResultEnvelope | null = null
+  ) {
     await program.methods
-      .updateJob(uri)
-      .accountsPartial({ agent: agent.publicKey, job: jobPDA })
+      .updateJob(uri, hash, envelope)
+      .accountsPartial({
+        agent: agent.publicKey,
+        job: jobPDA,
+        agentProfile: await assignedProfilePDA(jobPDA),
+        jobExtension: await jobExtensionPDA(jobPDA),
+      })
       .signers([agent])
       .rpc();
   }
@@ -105,17 +237,21 @@ describe("agent-protocol", () => {
     agentWallet: PublicKey,
     agentProfilePDA: PublicKey,
     jobPDA: PublicKey,
-    parentJobPDA: PublicKey | null = null
+    parentJobPDA: PublicKey | null = null,
+    teamPayout: TeamPayout | null = null,
+    bonus: BN | null = null
   ) {
-    await program.methods
-      .releasePayment()
+    return await program.methods
+      .releasePayment(bonus)
       .accountsPartial({
         client: client.publicKey,
         agent: agentWallet,
         agentProfile: agentProfilePDA,
         job: jobPDA,
         parentJob: parentJobPDA,
+        team: teamPayout?.team ?? null,
       })
+      .remainingAccounts(teamRemainingAccounts(teamPayout))
       .signers([client])
       .rpc();
   }
@@ -125,7 +261,8 @@ describe("agent-protocol", () => {
     agentProfilePDA: PublicKey,
     jobPDA: PublicKey,
     clientPubkey: PublicKey,
-    parentJobPDA: PublicKey | null = null
+    parentJobPDA: PublicKey | null = null,
+    teamPayout: TeamPayout | null = null
   ) {
     await program.methods
       .autoRelease()
@@ -135,14 +272,40 @@ describe("agent-protocol", () => {
         job: jobPDA,
         client: clientPubkey,
         parentJob: parentJobPDA,
+        team: teamPayout?.team ?? null,
       })
+      .remainingAccounts(teamRemainingAccounts(teamPayout))
       .rpc();
   }
 
+  // Profile of the job's assigned agent; null for an unclaimed bounty
+  async function assignedProfilePDA(jobPDA: PublicKey) {
+    const { agent } = await program.account.job.fetch(jobPDA);
+    return agent.equals(PublicKey.default) ? null : getAgentProfilePDA(agent)[0];
+  }
+
+  // The job's extension PDA; null for a job created without one
+  async function jobExtensionPDA(jobPDA: PublicKey) {
+    const { hasExtension } = await program.account.job.fetch(jobPDA);
+    return hasExtension ? getJobExtensionPDA(jobPDA)[0] : null;
+  }
+
+  async function payoutWallet(agentProfilePDA: PublicKey) {
+    return (await program.account.agentProfile.fetch(agentProfilePDA)).payout;
+  }
+
   async function cancelJob(client: Keypair, jobPDA: PublicKey) {
+    const { parentJob, teamSize, hasExtension } = await program.account.job.fetch(jobPDA);
     await program.methods
       .cancelJob()
-      .accountsPartial({ client: client.publicKey, job: jobPDA })
+      .accountsPartial({
+        client: client.publicKey,
+        job: jobPDA,
+        agentProfile: await assignedProfilePDA(jobPDA),
+        parentJob,
+        team: teamSize > 0 ? getTeamPDA(jobPDA)[0] : null,
+        jobExtension: hasExtension ? getJobExtensionPDA(jobPDA)[0] : null,
+      })
       .signers([client])
       .rpc();
   }
@@ -152,134 +315,561 @@ describe("agent-protocol", () => {
     parentJobPDA: PublicKey,
     subAgentProfilePDA: PublicKey,
     amount: BN,
-    desc = "Subtask"
+    desc = "Subtask",
+    spec: JobSpec | null = null
   ) {
-    const ts = nextTs();
-    const [childPDA] = getJobPDA(agent.publicKey, subAgentProfilePDA, ts);
+    const nonce = await nextJobNonce(agent.publicKey);
+    const [childPDA] = getJobPDA(agent.publicKey, nonce);
+    const { teamSize, hasExtension } = await program.account.job.fetch(parentJobPDA);
     await program.methods
-      .delegateTask(desc, amount, ts)
+      .delegateTask(desc, amount, spec)
       .accountsPartial({
         delegatingAgent: agent.publicKey,
         parentJob: parentJobPDA,
+        agentProfile: await assignedProfilePDA(parentJobPDA),
+        team: teamSize > 0 ? getTeamPDA(parentJobPDA)[0] : null,
+        parentExtension: hasExtension ? getJobExtensionPDA(parentJobPDA)[0] : null,
         subAgentProfile: subAgentProfilePDA,
+        childJob: childPDA,
       })
       .signers([agent])
       .rpc();
-    return { childJobPDA: childPDA, ts };
+    return { childJobPDA: childPDA, nonce };
   }
 
   async function raiseDispute(disputant: Keypair, jobPDA: PublicKey) {
     await program.methods
       .raiseDispute()
-      .accountsPartial({ disputant: disputant.publicKey, job: jobPDA })
+      .accountsPartial({
+        disputant: disputant.publicKey,
+        job: jobPDA,
+        agentProfile: await assignedProfilePDA(jobPDA),
+      })
       .signers([disputant])
       .rpc();
   }
 
-  async function rateAgent(
+  async function stakeAgent(owner: Keypair, agentProfilePDA: PublicKey, amount: BN) {
+    await program.methods
+      .stakeAgent(amount)
+      .accountsPartial({ owner: owner.publicKey, agentProfile: agentProfilePDA })
+      .signers([owner])
+      .rpc();
+  }
+
+  async function postBounty(
     client: Keypair,
-    jobPDA: PublicKey,
+    requiredCaps = 0,
+    minRatingX100 = 0,
+    claimWindowSecs: BN | null = null,
+    payment = PAYMENT,
+    autoReleaseSecs: BN | null = null
+  ) {
+    const nonce = await nextJobNonce(client.publicKey);
+    const [jobPDA] = getJobPDA(client.publicKey, nonce);
+    await program.methods
+      .postBounty("Open bounty", payment, requiredCaps, minRatingX100, claimWindowSecs, autoReleaseSecs, null)
+      .accountsPartial({ client: client.publicKey, job: jobPDA })
+      .signers([client])
+      .rpc();
+    return { jobPDA, nonce };
+  }
+
+  async function claimBounty(agent: Keypair, agentProfilePDA: PublicKey, jobPDA: PublicKey) {
+    await program.methods
+      .claimBounty()
+      .accountsPartial({ agent: agent.publicKey, agentProfile: agentProfilePDA, job: jobPDA })
+      .signers([agent])
+      .rpc();
+  }
+
+  async function createJobRequest(
+    client: Keypair,
+    biddingSecs: BN,
+    revealSecs: BN | null = null,
+    maxBudget = new BN(2 * LAMPORTS_PER_SOL)
+  ) {
+    const [requestPDA] = getJobRequestPDA(client.publicKey, await nextJobNonce(client.publicKey));
+    await program.methods
+      .createJobRequest("Quote me a full audit", maxBudget, biddingSecs, revealSecs, null)
+      .accountsPartial({ client: client.publicKey })
+      .signers([client])
+      .rpc();
+    return requestPDA;
+  }
+
+  async function submitBid(
+    agent: Keypair,
     agentProfilePDA: PublicKey,
-    score: number
+    requestPDA: PublicKey,
+    price: BN,
+    eta = new BN(86_400),
+    uri = "ipfs://proposal",
+    commitment: number[] | null = null
   ) {
     await program.methods
-      .rateAgent(score)
+      .submitBid(price, eta, uri, commitment)
       .accountsPartial({
-        client: client.publicKey,
-        job: jobPDA,
+        agent: agent.publicKey,
         agentProfile: agentProfilePDA,
+        jobRequest: requestPDA,
       })
+      .signers([agent])
+      .rpc();
+    return getBidPDA(requestPDA, agentProfilePDA)[0];
+  }
+
+  const bidCommitment = (price: BN, eta: BN, uri: string, salt: Buffer) =>
+    Array.from(
+      createHash("sha256")
+        .update(price.toArrayLike(Buffer, "le", 8))
+        .update(eta.toTwos(64).toArrayLike(Buffer, "le", 8))
+        .update(Buffer.from(uri))
+        .update(salt)
+        .digest()
+    );
+
+  async function createSubscription(
+    client: Keypair,
+    agentProfilePDA: PublicKey,
+    periodSecs: BN,
+    periods: number,
+    amount = PRICE
+  ) {
+    const [subPDA] = getSubscriptionPDA(client.publicKey, await nextJobNonce(client.publicKey));
+    await program.methods
+      .createSubscription(amount, periodSecs, periods)
+      .accountsPartial({ client: client.publicKey, agentProfile: agentProfilePDA })
       .signers([client])
       .rpc();
+    return subPDA;
   }
 
-  // Helper to assert an Anchor error code
-  function expectAnchorError(err: any, code: string) {
-    const c = err?.error?.errorCode?.code;
-    if (c) {
-      expect(c).to.equal(code);
-    } else {
-      const msg = (err?.message || "") + (err?.logs?.join("\n") || "");
-      expect(msg).to.include(code);
-    }
+  async function claimPeriod(agent: Keypair, agentProfilePDA: PublicKey, subPDA: PublicKey) {
+    await program.methods
+      .claimPeriod()
+      .accountsPartial({
+        agent: agent.publicKey,
+        agentProfile: agentProfilePDA,
+        payout: await payoutWallet(agentProfilePDA),
+        subscription: subPDA,
+      })
+      .signers([agent])
+      .rpc();
   }
 
-  // ─── Shared state set up once ───
+  async function withdrawStream(agent: Keypair, agentProfilePDA: PublicKey, jobPDA: PublicKey) {
+    await program.methods
+      .withdrawStream()
+      .accountsPartial({
+        agent: agent.publicKey,
+        agentProfile: agentProfilePDA,
+        payout: await payoutWallet(agentProfilePDA),
+        job: jobPDA,
+        jobExtension: await jobExtensionPDA(jobPDA),
+      })
+      .signers([agent])
+      .rpc();
+  }
 
-  let agentOwnerA: Keypair;
-  let agentOwnerB: Keypair;
-  let agentProfileA: PublicKey;
-  let agentProfileB: PublicKey;
-  let clientKp: Keypair;
+  async function commitResult(agent: Keypair, jobPDA: PublicKey, hash: number[]) {
+    await program.methods
+      .commitResult(hash)
+      .accountsPartial({
+        agent: agent.publicKey,
+        job: jobPDA,
+        agentProfile: await assignedProfilePDA(jobPDA),
+      })
+      .signers([agent])
+      .rpc();
+  }
 
-  before(async () => {
-    agentOwnerA = Keypair.generate();
-    agentOwnerB = Keypair.generate();
-    clientKp = Keypair.generate();
+  async function setEncryptionKey(owner: Keypair, profilePDA: PublicKey, key: number[] | null) {
+    await program.methods
+      .setEncryptionKey(key)
+      .accountsPartial({ owner: owner.publicKey, agentProfile: profilePDA })
+      .signers([owner])
+      .rpc();
+  }
 
-    await airdrop(agentOwnerA.publicKey);
-    await airdrop(agentOwnerB.publicKey);
-    await airdrop(clientKp.publicKey, 100 * LAMPORTS_PER_SOL);
+  // ─── Encrypted delivery (mirrors programs/agent-protocol/src/envelope.rs) ───
 
-    agentProfileA = await registerAgent(agentOwnerA, "Aurora", PRICE);
-    agentProfileB = await registerAgent(agentOwnerB, "CodeAuditor", PRICE);
-  });
+  const x25519Keypair = () => {
+    const { publicKey, privateKey } = generateKeyPairSync("x25519");
+    // Raw 32-byte key is the tail of the SPKI encoding
+    const raw = publicKey.export({ format: "der", type: "spki" }).subarray(-32);
+    return { publicKey, privateKey, raw: Array.from(raw) };
+  };
 
-  // ═══════════════════════════════════════
-  //  register_agent
-  // ═══════════════════════════════════════
+  const deriveWrapKey = (
+    shared: Buffer,
+    jobPDA: PublicKey,
+    clientKey: number[],
+    agentKey: number[]
+  ) =>
+    createHash("sha256")
+      .update("agent-protocol/result-key/v1")
+      .update(shared)
+      .update(jobPDA.toBuffer())
+      .update(Buffer.from(clientKey))
+      .update(Buffer.from(agentKey))
+      .digest();
+
+  const envelopeAad = (jobPDA: PublicKey, resultHash: number[]) =>
+    Buffer.concat([jobPDA.toBuffer(), Buffer.from(resultHash)]);
+
+  function sealEnvelope(
+    agentPrivate: KeyObject,
+    agentKey: number[],
+    clientPublic: KeyObject,
+    clientKey: number[],
+    jobPDA: PublicKey,
+    resultHash: number[],
+    contentKey: Buffer
+  ): ResultEnvelope {
+    const shared = diffieHellman({ privateKey: agentPrivate, publicKey: clientPublic });
+    const nonce = randomBytes(12);
+    const cipher = createCipheriv(
+      "chacha20-poly1305",
+      deriveWrapKey(shared, jobPDA, clientKey, agentKey),
+      nonce,
+      { authTagLength: 16 }
+    );
+    cipher.setAAD(envelopeAad(jobPDA, resultHash), { plaintextLength: contentKey.length });
+    const wrapped = Buffer.concat([cipher.update(contentKey), cipher.final(), cipher.getAuthTag()]);
+    return {
+      version: 1,
+      agentKey,
+      nonce: Array.from(nonce),
+      wrappedKey: Array.from(wrapped),
+    };
+  }
 
-  describe("register_agent", () => {
-    it("registers agent with valid params and correct PDA fields", async () => {
-      const kp = Keypair.generate();
-      await airdrop(kp.publicKey);
-      const pda = await registerAgent(kp, "Fresh", new BN(100_000));
-      const acct = await program.account.agentProfile.fetch(pda);
-      expect(acct.owner.toBase58()).to.equal(kp.publicKey.toBase58());
-      expect(acct.name).to.equal("Fresh");
-      expect(acct.priceLamports.toNumber()).to.equal(100_000);
-      expect(acct.isActive).to.be.true;
-      expect(acct.jobsCompleted).to.equal(0);
-      expect(acct.ratingCount).to.equal(0);
-      expect(acct.ratingSum.toNumber()).to.equal(0);
-      expect(acct.bump).to.be.greaterThan(0);
-    });
+  async function invokeTeam(
+    client: Keypair,
+    leadProfilePDA: PublicKey,
+    otherProfilePDAs: PublicKey[],
+    sharesBps: number[],
+    requireAllSignoff = false,
+    payment = new BN(2 * LAMPORTS_PER_SOL)
+  ) {
+    const jobPDA = await nextJobPDA(client.publicKey);
+    await program.methods
+      .invokeTeam("Team audit", payment, null, sharesBps, requireAllSignoff, null)
+      .accountsPartial({ client: client.publicKey, leadProfile: leadProfilePDA, job: jobPDA })
+      .remainingAccounts(
+        otherProfilePDAs.flatMap((pubkey) => [
+          { pubkey, isWritable: false, isSigner: false },
+          { pubkey: getAccessListPDA(pubkey)[0], isWritable: false, isSigner: false },
+        ])
+      )
+      .signers([client])
+      .rpc();
+    return { jobPDA, teamPDA: getTeamPDA(jobPDA)[0] };
+  }
 
-    it("rejects duplicate registration (same owner)", async () => {
-      const kp = Keypair.generate();
-      await airdrop(kp.publicKey);
-      await registerAgent(kp, "First");
-      try {
-        await registerAgent(kp, "Second");
-        expect.fail("Should have thrown");
-      } catch (err: any) {
-        // PDA already initialised — Anchor or runtime error
-        expect(err).to.exist;
-      }
-    });
+  async function invokeAgentsBatch(
+    client: Keypair,
+    entries: { profile: PublicKey; title: string; payment: BN }[],
+    jobPDAs?: PublicKey[]
+  ) {
+    const first = await nextJobNonce(client.publicKey);
+    const jobs = jobPDAs ?? entries.map((_, i) => getJobPDA(client.publicKey, first.addn(i))[0]);
+    await program.methods
+      .invokeAgentsBatch(
+        entries.map(({ title, payment }) => ({ title, paymentLamports: payment, spec: null })),
+        null
+      )
+      .accountsPartial({ client: client.publicKey })
+      .remainingAccounts(
+        entries.flatMap(({ profile }, i) => [
+          { pubkey: profile, isWritable: true, isSigner: false },
+          { pubkey: getAccessListPDA(profile)[0], isWritable: false, isSigner: false },
+          { pubkey: jobs[i], isWritable: true, isSigner: false },
+        ])
+      )
+      .signers([client])
+      .rpc();
+    return jobs;
+  }
 
-    it("rejects empty name", async () => {
-      const kp = Keypair.generate();
-      await airdrop(kp.publicKey);
-      try {
-        await registerAgent(kp, "");
-        expect.fail("Should have thrown");
-      } catch (err: any) {
-        expectAnchorError(err, "EmptyDescription");
-      }
-    });
+  async function proposePartialRelease(client: Keypair, jobPDA: PublicKey, agentLamports: BN) {
+    await program.methods
+      .proposePartialRelease(agentLamports)
+      .accountsPartial({
+        client: client.publicKey,
+        job: jobPDA,
+        jobExtension: await jobExtensionPDA(jobPDA),
+      })
+      .signers([client])
+      .rpc();
+  }
 
-    it("rejects zero price", async () => {
-      const kp = Keypair.generate();
-      await airdrop(kp.publicKey);
-      try {
-        await registerAgent(kp, "Agent", new BN(0));
-        expect.fail("Should have thrown");
-      } catch (err: any) {
-        expectAnchorError(err, "InvalidPrice");
-      }
-    });
-  });
+  async function acceptPartialRelease(
+    agent: Keypair,
+    agentProfilePDA: PublicKey,
+    jobPDA: PublicKey,
+    clientPubkey: PublicKey,
+    agentLamports: BN
+  ) {
+    await program.methods
+      .acceptPartialRelease(agentLamports)
+      .accountsPartial({
+        agent: agent.publicKey,
+        agentProfile: agentProfilePDA,
+        payout: await payoutWallet(agentProfilePDA),
+        job: jobPDA,
+        client: clientPubkey,
+        parentJob: null,
+        team: null,
+      })
+      .signers([agent])
+      .rpc();
+  }
+
+  async function reassignJob(
+    client: Keypair,
+    jobPDA: PublicKey,
+    newAgentProfilePDA: PublicKey,
+    stakeRequirement: { minLamports: BN; slashBps: number } | null = null
+  ) {
+    const [stakePDA] = getStakePDA(newAgentProfilePDA);
+    const staked = (await connection.getAccountInfo(stakePDA)) !== null;
+    await program.methods
+      .reassignJob(stakeRequirement)
+      .accountsPartial({
+        client: client.publicKey,
+        job: jobPDA,
+        jobExtension: await jobExtensionPDA(jobPDA),
+        agentProfile: await assignedProfilePDA(jobPDA),
+        newAgentProfile: newAgentProfilePDA,
+        newAgentStake: staked ? stakePDA : null,
+      })
+      .signers([client])
+      .rpc();
+  }
+
+  async function cancelJobTree(client: Keypair, jobPDA: PublicKey, children: PublicKey[]) {
+    const subAgentProfiles = await Promise.all(children.map(assignedProfilePDA));
+    const childClients = await Promise.all(
+      children.map(async (child) => (await program.account.job.fetch(child)).client)
+    );
+    const { parentJob, teamSize, hasExtension } = await program.account.job.fetch(jobPDA);
+    const remaining = children.flatMap((child, i) => [
+      { pubkey: child, isWritable: true, isSigner: false },
+      { pubkey: subAgentProfiles[i], isWritable: true, isSigner: false },
+      { pubkey: childClients[i], isWritable: true, isSigner: false },
+    ]);
+    await program.methods
+      .cancelJobTree()
+      .accountsPartial({
+        client: client.publicKey,
+        job: jobPDA,
+        agentProfile: await assignedProfilePDA(jobPDA),
+        parentJob,
+        team: teamSize > 0 ? getTeamPDA(jobPDA)[0] : null,
+        jobExtension: hasExtension ? getJobExtensionPDA(jobPDA)[0] : null,
+      })
+      .remainingAccounts(remaining)
+      .signers([client])
+      .rpc();
+  }
+
+  async function setMaxConcurrentJobs(owner: Keypair, profilePDA: PublicKey, max: number) {
+    await program.methods
+      .setMaxConcurrentJobs(max)
+      .accountsPartial({ owner: owner.publicKey, agentProfile: profilePDA })
+      .signers([owner])
+      .rpc();
+  }
+
+  async function setAgentKeys(
+    owner: Keypair,
+    profilePDA: PublicKey,
+    payout: PublicKey,
+    operator: PublicKey | null
+  ) {
+    await program.methods
+      .setAgentKeys(payout, operator)
+      .accountsPartial({ owner: owner.publicKey, agentProfile: profilePDA })
+      .signers([owner])
+      .rpc();
+  }
+
+  async function proposeAgentTransfer(owner: Keypair, profilePDA: PublicKey, newOwner: PublicKey | null) {
+    await program.methods
+      .proposeAgentTransfer(newOwner)
+      .accountsPartial({ owner: owner.publicKey, agentProfile: profilePDA })
+      .signers([owner])
+      .rpc();
+  }
+
+  async function acceptAgentTransfer(newOwner: Keypair, profilePDA: PublicKey) {
+    await program.methods
+      .acceptAgentTransfer()
+      .accountsPartial({ newOwner: newOwner.publicKey, agentProfile: profilePDA })
+      .signers([newOwner])
+      .rpc();
+  }
+
+  async function setAgentMetadata(
+    owner: Keypair,
+    profilePDA: PublicKey,
+    endpointUri: string,
+    metadataUri = "",
+    inputFormats = 1 << 0, // FORMAT_TEXT
+    schemaVersion = 1
+  ) {
+    const metadataHash = metadataUri ? sha256(metadataUri) : Array(32).fill(0);
+    await program.methods
+      .setAgentMetadata(endpointUri, metadataUri, metadataHash, inputFormats, schemaVersion)
+      .accountsPartial({ owner: owner.publicKey, agentProfile: profilePDA })
+      .signers([owner])
+      .rpc();
+    const [metadataPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), profilePDA.toBuffer()],
+      program.programId
+    );
+    return metadataPDA;
+  }
+
+  async function heartbeat(agent: Keypair, profilePDA: PublicKey) {
+    await program.methods
+      .heartbeat()
+      .accountsPartial({ agent: agent.publicKey, agentProfile: profilePDA })
+      .signers([agent])
+      .rpc();
+  }
+
+  async function setAccessList(
+    owner: Keypair,
+    profilePDA: PublicKey,
+    mode: { open: {} } | { allowlist: {} } | { blocklist: {} },
+    clients: PublicKey[]
+  ) {
+    await program.methods
+      .setAccessList(mode, clients)
+      .accountsPartial({ owner: owner.publicKey, agentProfile: profilePDA })
+      .signers([owner])
+      .rpc();
+  }
+
+  async function setPriceTable(
+    owner: Keypair,
+    profilePDA: PublicKey,
+    table: { capability: number; tier: number; priceLamports: BN }[]
+  ) {
+    await program.methods
+      .setPriceTable(table)
+      .accountsPartial({ owner: owner.publicKey, agentProfile: profilePDA })
+      .signers([owner])
+      .rpc();
+  }
+
+  async function rateAgent(
+    client: Keypair,
+    jobPDA: PublicKey,
+    agentProfilePDA: PublicKey,
+    score: number
+  ) {
+    await program.methods
+      .rateAgent(score)
+      .accountsPartial({
+        client: client.publicKey,
+        job: jobPDA,
+        agentProfile: agentProfilePDA,
+      })
+      .signers([client])
+      .rpc();
+  }
+
+  // Helper to assert an Anchor error code
+  function expectAnchorError(err: any, code: string) {
+    const c = err?.error?.errorCode?.code;
+    if (c) {
+      expect(c).to.equal(code);
+    } else {
+      const msg = (err?.message || "") + (err?.logs?.join("\n") || "");
+      expect(msg).to.include(code);
+    }
+  }
+
+  // ─── Shared state set up once ───
+
+  let agentOwnerA: Keypair;
+  let agentOwnerB: Keypair;
+  let agentProfileA: PublicKey;
+  let agentProfileB: PublicKey;
+  let clientKp: Keypair;
+
+  before(async () => {
+    agentOwnerA = Keypair.generate();
+    agentOwnerB = Keypair.generate();
+    clientKp = Keypair.generate();
+
+    await airdrop(agentOwnerA.publicKey);
+    await airdrop(agentOwnerB.publicKey);
+    await airdrop(clientKp.publicKey, 100 * LAMPORTS_PER_SOL);
+
+    agentProfileA = await registerAgent(agentOwnerA, "Aurora", PRICE);
+    agentProfileB = await registerAgent(agentOwnerB, "CodeAuditor", PRICE);
+  });
+
+  // ═══════════════════════════════════════
+  //  register_agent
+  // ═══════════════════════════════════════
+
+  describe("register_agent", () => {
+    it("registers agent with valid params and correct PDA fields", async () => {
+      const kp = Keypair.generate();
+      await airdrop(kp.publicKey);
+      const pda = await registerAgent(kp, "Fresh", new BN(100_000));
+      const acct = await program.account.agentProfile.fetch(pda);
+      expect(acct.owner.toBase58()).to.equal(kp.publicKey.toBase58());
+      expect(acct.name).to.equal("Fresh");
+      expect(acct.priceLamports.toNumber()).to.equal(100_000);
+      expect(acct.isActive).to.be.true;
+      expect(acct.jobsCompleted).to.equal(0);
+      expect(acct.ratingCount).to.equal(0);
+      expect(acct.ratingSum.toNumber()).to.equal(0);
+      expect(acct.bump).to.be.greaterThan(0);
+    });
+
+    it("rejects duplicate registration (same owner)", async () => {
+      const kp = Keypair.generate();
+      await airdrop(kp.publicKey);
+      await registerAgent(kp, "First");
+      try {
+        await registerAgent(kp, "Second");
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        // PDA already initialised — Anchor or runtime error
+        expect(err).to.exist;
+      }
+    });
+
+    it("rejects empty name", async () => {
+      const kp = Keypair.generate();
+      await airdrop(kp.publicKey);
+      try {
+        await registerAgent(kp, "");
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "EmptyDescription");
+      }
+    });
+
+    it("rejects zero price", async () => {
+      const kp = Keypair.generate();
+      await airdrop(kp.publicKey);
+      try {
+        await registerAgent(kp, "Agent", new BN(0));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidPrice");
+      }
+    });
+  });
 
   // ═══════════════════════════════════════
   //  invoke_agent
@@ -293,7 +883,7 @@ describe("agent-protocol", () => {
       expect(job.agent.toBase58()).to.equal(agentOwnerA.publicKey.toBase58());
       expect(job.escrowLamports.toNumber()).to.equal(PAYMENT.toNumber());
       expect(Object.keys(job.status)[0]).to.equal("pending");
-      expect(job.description).to.equal("Test task");
+      expect(job.title).to.equal("Test task");
       expect(job.activeChildren).to.equal(0);
       expect(job.parentJob).to.be.null;
     });
@@ -371,6 +961,23 @@ describe("agent-protocol", () => {
       }
     });
 
+    it("stores the result hash alongside the URI", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA, "ipfs://hashed-result");
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.resultHash).to.deep.equal(sha256("ipfs://hashed-result"));
+    });
+
+    it("rejects an all-zero result hash", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      try {
+        await updateJob(agentOwnerA, jobPDA, "ipfs://x", new Array(32).fill(0));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "EmptyResultHash");
+      }
+    });
+
     it("rejects if active_children > 0", async () => {
       const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
       await delegateTask(
@@ -539,53 +1146,2132 @@ describe("agent-protocol", () => {
         );
         expect.fail("Should have thrown");
       } catch (err: any) {
-        expectAnchorError(err, "InvalidJobStatus");
+        expectAnchorError(err, "InvalidJobStatus");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  cancel_job
+  // ═══════════════════════════════════════
+
+  describe("cancel_job", () => {
+    it("cancels Pending job and refunds client", async () => {
+      const balBefore = await connection.getBalance(clientKp.publicKey);
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      const balAfterInvoke = await connection.getBalance(clientKp.publicKey);
+
+      await cancelJob(clientKp, jobPDA);
+      const balAfterCancel = await connection.getBalance(clientKp.publicKey);
+
+      // Client should get back escrow + rent (minus tx fees)
+      // balAfterCancel should be close to balBefore (just tx fees lost)
+      const diff = balBefore - balAfterCancel;
+      // Should only be ~2 tx fees (~10000 lamports)
+      expect(diff).to.be.lessThan(100_000);
+    });
+
+    it("rejects if status != Pending (InProgress)", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      // Agent starts working (delegates to make it InProgress via delegate, or via update)
+      await delegateTask(
+        agentOwnerA,
+        jobPDA,
+        agentProfileB,
+        new BN(LAMPORTS_PER_SOL / 10)
+      );
+      // Job is now InProgress (delegate auto-flips)
+      try {
+        await cancelJob(clientKp, jobPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidJobStatus");
+      }
+    });
+
+    it("rejects non-client signer", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      try {
+        await cancelJob(agentOwnerA, jobPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "Unauthorized");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  delegate_task
+  // ═══════════════════════════════════════
+
+  describe("delegate_task", () => {
+    it("creates child job linked to parent with correct escrow", async () => {
+      const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
+      const delegateAmt = new BN(LAMPORTS_PER_SOL / 4);
+      const { childJobPDA } = await delegateTask(
+        agentOwnerA,
+        parentPDA,
+        agentProfileB,
+        delegateAmt
+      );
+
+      const child = await program.account.job.fetch(childJobPDA);
+      expect(child.parentJob!.toBase58()).to.equal(parentPDA.toBase58());
+      expect(child.escrowLamports.toNumber()).to.equal(delegateAmt.toNumber());
+      expect(child.agent.toBase58()).to.equal(agentOwnerB.publicKey.toBase58());
+      expect(Object.keys(child.status)[0]).to.equal("pending");
+
+      const parent = await program.account.job.fetch(parentPDA);
+      expect(parent.activeChildren).to.equal(1);
+      expect(parent.escrowLamports.toNumber()).to.equal(
+        PAYMENT.toNumber() - delegateAmt.toNumber()
+      );
+      // Parent auto-flipped to InProgress
+      expect(Object.keys(parent.status)[0]).to.equal("inProgress");
+    });
+
+    it("rejects delegation exceeding parent escrow", async () => {
+      const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
+      try {
+        await delegateTask(
+          agentOwnerA,
+          parentPDA,
+          agentProfileB,
+          new BN(PAYMENT.toNumber() + 1) // more than escrowed
+        );
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InsufficientEscrow");
+      }
+    });
+
+    it("rejects non-agent signer", async () => {
+      const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
+      try {
+        await delegateTask(
+          clientKp, // wrong signer
+          parentPDA,
+          agentProfileB,
+          new BN(LAMPORTS_PER_SOL / 10)
+        );
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "Unauthorized");
+      }
+    });
+
+    it("rejects empty description", async () => {
+      const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
+      try {
+        await program.methods
+          .delegateTask("", new BN(LAMPORTS_PER_SOL / 10), null)
+          .accountsPartial({
+            delegatingAgent: agentOwnerA.publicKey,
+            parentJob: parentPDA,
+            agentProfile: agentProfileA,
+            team: null,
+            parentExtension: null,
+            subAgentProfile: agentProfileB,
+            childJob: await nextJobPDA(agentOwnerA.publicKey),
+          })
+          .signers([agentOwnerA])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "EmptyDescription");
+      }
+    });
+
+    it("cancelling a child returns its escrow and slot to the parent", async () => {
+      const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
+      const { childJobPDA } = await delegateTask(
+        agentOwnerA,
+        parentPDA,
+        agentProfileB,
+        new BN(LAMPORTS_PER_SOL / 4)
+      );
+      await cancelJob(agentOwnerA, childJobPDA);
+
+      const parent = await program.account.job.fetch(parentPDA);
+      expect(parent.activeChildren).to.equal(0);
+      expect(parent.escrowLamports.toNumber()).to.equal(PAYMENT.toNumber());
+      expect(await connection.getAccountInfo(childJobPDA)).to.be.null;
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  raise_dispute
+  // ═══════════════════════════════════════
+
+  describe("raise_dispute", () => {
+    it("client can raise dispute", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await raiseDispute(clientKp, jobPDA);
+      const job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("disputed");
+      expect(job.disputedAt).to.not.be.null;
+    });
+
+    it("agent can raise dispute", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await raiseDispute(agentOwnerA, jobPDA);
+      const job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("disputed");
+    });
+
+    it("rejects if status is Cancelled", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await cancelJob(clientKp, jobPDA);
+      // Account is closed after cancel, so dispute should fail
+      try {
+        await raiseDispute(clientKp, jobPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err).to.exist;
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  resolve_dispute_by_timeout
+  // ═══════════════════════════════════════
+
+  describe("resolve_dispute_by_timeout", () => {
+    it("rejects if status != Disputed", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      try {
+        await program.methods
+          .resolveDisputeByTimeout()
+          .accountsPartial({
+            client: clientKp.publicKey,
+            job: jobPDA,
+            agentProfile: agentProfileA,
+            parentJob: null,
+            team: null,
+            jobExtension: null,
+          })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidJobStatus");
+      }
+    });
+
+    it("rejects if timeout not reached", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await raiseDispute(clientKp, jobPDA);
+      try {
+        await program.methods
+          .resolveDisputeByTimeout()
+          .accountsPartial({
+            client: clientKp.publicKey,
+            job: jobPDA,
+            agentProfile: agentProfileA,
+            parentJob: null,
+            team: null,
+            jobExtension: null,
+          })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "DisputeTimeoutNotReached");
+      }
+    });
+
+    it("rejects resolving a parent whose children are still open", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 4));
+      await raiseDispute(clientKp, jobPDA);
+      try {
+        await program.methods
+          .resolveDisputeByTimeout()
+          .accountsPartial({
+            client: clientKp.publicKey,
+            job: jobPDA,
+            agentProfile: agentProfileA,
+            parentJob: null,
+            team: null,
+            jobExtension: null,
+          })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "UnresolvedChildren");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  rate_agent
+  // ═══════════════════════════════════════
+
+  describe("rate_agent", () => {
+    it("creates rating and updates agent profile sums", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
+
+      const profileBefore = await program.account.agentProfile.fetch(agentProfileA);
+      await rateAgent(clientKp, jobPDA, agentProfileA, 5);
+
+      const profileAfter = await program.account.agentProfile.fetch(agentProfileA);
+      expect(profileAfter.ratingCount).to.equal(profileBefore.ratingCount + 1);
+      expect(profileAfter.ratingSum.toNumber()).to.equal(
+        profileBefore.ratingSum.toNumber() + 5
+      );
+    });
+
+    it("rejects score outside 1-5 (score 0)", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
+      try {
+        await rateAgent(clientKp, jobPDA, agentProfileA, 0);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidRating");
+      }
+    });
+
+    it("rejects score outside 1-5 (score 6)", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
+      try {
+        await rateAgent(clientKp, jobPDA, agentProfileA, 6);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidRating");
+      }
+    });
+
+    it("rejects duplicate rating (same job)", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
+      await rateAgent(clientKp, jobPDA, agentProfileA, 4);
+      try {
+        await rateAgent(clientKp, jobPDA, agentProfileA, 3);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        // PDA already init'd
+        expect(err).to.exist;
+      }
+    });
+
+    it("rejects rating a profile other than the job's agent", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
+      try {
+        await rateAgent(clientKp, jobPDA, agentProfileB, 5);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "Unauthorized");
+      }
+    });
+
+    it("rejects rating before payment (status != Finalized)", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA); // Completed, not Finalized
+      try {
+        await rateAgent(clientKp, jobPDA, agentProfileA, 5);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidJobStatus");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Agent staking
+  // ═══════════════════════════════════════
+
+  describe("agent staking", () => {
+    it("stake_agent escrows SOL in the stake vault", async () => {
+      const owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      const profilePDA = await registerAgent(owner, "Staker");
+      const stakeAmt = new BN(LAMPORTS_PER_SOL);
+
+      await stakeAgent(owner, profilePDA, stakeAmt);
+      await stakeAgent(owner, profilePDA, stakeAmt); // top-up reuses the vault
+
+      const [stakePDA] = getStakePDA(profilePDA);
+      const stake = await program.account.agentStake.fetch(stakePDA);
+      expect(stake.agentProfile.toBase58()).to.equal(profilePDA.toBase58());
+      expect(stake.stakedLamports.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+      expect(stake.unstakingLamports.toNumber()).to.equal(0);
+    });
+
+    it("request_unstake queues lamports and withdraw enforces cooldown", async () => {
+      const owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      const profilePDA = await registerAgent(owner, "Unstaker");
+      await stakeAgent(owner, profilePDA, new BN(LAMPORTS_PER_SOL));
+
+      await program.methods
+        .requestUnstake(new BN(LAMPORTS_PER_SOL / 2))
+        .accountsPartial({ owner: owner.publicKey, agentProfile: profilePDA })
+        .signers([owner])
+        .rpc();
+
+      const [stakePDA] = getStakePDA(profilePDA);
+      const stake = await program.account.agentStake.fetch(stakePDA);
+      expect(stake.stakedLamports.toNumber()).to.equal(LAMPORTS_PER_SOL / 2);
+      expect(stake.unstakingLamports.toNumber()).to.equal(LAMPORTS_PER_SOL / 2);
+      expect(stake.unstakeAvailableAt).to.not.be.null;
+
+      try {
+        await program.methods
+          .withdrawStake()
+          .accountsPartial({ owner: owner.publicKey, agentProfile: profilePDA })
+          .signers([owner])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "UnstakeCooldownActive");
+      }
+    });
+
+    it("rejects stake operations from a non-owner", async () => {
+      const outsider = Keypair.generate();
+      await airdrop(outsider.publicKey);
+      try {
+        await stakeAgent(outsider, agentProfileA, new BN(LAMPORTS_PER_SOL));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "Unauthorized");
+      }
+    });
+
+    it("invoke_agent enforces the client's minimum stake", async () => {
+      const owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      const profilePDA = await registerAgent(owner, "Collateral");
+      await stakeAgent(owner, profilePDA, new BN(LAMPORTS_PER_SOL));
+
+      const { jobPDA } = await invokeAgent(
+        clientKp,
+        profilePDA,
+        PAYMENT,
+        null,
+        "Staked task",
+        { stakeRequirement: { minLamports: new BN(LAMPORTS_PER_SOL), slashBps: 2_500 } }
+      );
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.escrowLamports.toNumber()).to.equal(PAYMENT.toNumber());
+      expect(job.slashBps).to.equal(2_500);
+
+      try {
+        await invokeAgent(
+          clientKp,
+          profilePDA,
+          PAYMENT,
+          null,
+          "Staked task",
+          { stakeRequirement: { minLamports: new BN(2 * LAMPORTS_PER_SOL), slashBps: 2_500 } }
+        );
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InsufficientStake");
+      }
+
+      try {
+        await invokeAgent(
+          clientKp,
+          profilePDA,
+          PAYMENT,
+          null,
+          "Staked task",
+          { stakeRequirement: { minLamports: new BN(LAMPORTS_PER_SOL), slashBps: 5_001 } }
+        );
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidSlashBps");
+      }
+    });
+
+    it("raise_dispute records who raised it", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await raiseDispute(agentOwnerA, jobPDA);
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.disputedBy.toBase58()).to.equal(agentOwnerA.publicKey.toBase58());
+      expect(job.slashBps).to.equal(5_000);
+    });
+
+    it("withdraw_stake is refused while a client dispute is open", async () => {
+      const owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      const profilePDA = await registerAgent(owner, "Disputed");
+      await stakeAgent(owner, profilePDA, new BN(LAMPORTS_PER_SOL));
+      const { jobPDA } = await invokeAgent(clientKp, profilePDA);
+      await raiseDispute(clientKp, jobPDA);
+      expect((await program.account.agentProfile.fetch(profilePDA)).openDisputes).to.equal(1);
+
+      await program.methods
+        .requestUnstake(new BN(LAMPORTS_PER_SOL))
+        .accountsPartial({ owner: owner.publicKey, agentProfile: profilePDA })
+        .signers([owner])
+        .rpc();
+      try {
+        await program.methods
+          .withdrawStake()
+          .accountsPartial({ owner: owner.publicKey, agentProfile: profilePDA })
+          .signers([owner])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "DisputesOpen");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Open bounties
+  // ═══════════════════════════════════════
+
+  describe("open bounties", () => {
+    it("post_bounty escrows SOL with no agent assigned", async () => {
+      const { jobPDA } = await postBounty(clientKp, 0x02, 0, new BN(3600));
+      const job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("open");
+      expect(job.agent.toBase58()).to.equal(PublicKey.default.toBase58());
+      expect(job.requiredCapabilities).to.equal(0x02);
+      expect(job.claimDeadline).to.not.be.null;
+      expect(job.escrowLamports.toNumber()).to.equal(PAYMENT.toNumber());
+    });
+
+    it("starts the auto-release timer when the bounty is claimed", async () => {
+      const { jobPDA } = await postBounty(clientKp, 0, 0, null, PAYMENT, new BN(3600));
+      let job = await program.account.job.fetch(jobPDA);
+      expect(job.autoReleaseAt).to.be.null;
+      expect(job.autoReleaseDelay.toNumber()).to.equal(3600);
+
+      await claimBounty(agentOwnerA, agentProfileA, jobPDA);
+      job = await program.account.job.fetch(jobPDA);
+      const claimedAt = await connection.getBlockTime(await connection.getSlot());
+      expect(job.autoReleaseAt.toNumber()).to.be.closeTo(claimedAt + 3600, 5);
+    });
+
+    it("qualifying agent claims, completes and gets paid", async () => {
+      const { jobPDA } = await postBounty(clientKp, 0x02);
+      await claimBounty(agentOwnerA, agentProfileA, jobPDA);
+
+      let job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("pending");
+      expect(job.agent.toBase58()).to.equal(agentOwnerA.publicKey.toBase58());
+
+      await updateJob(agentOwnerA, jobPDA);
+      const balBefore = await connection.getBalance(agentOwnerA.publicKey);
+      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
+      const balAfter = await connection.getBalance(agentOwnerA.publicKey);
+      expect(balAfter - balBefore).to.equal(PAYMENT.toNumber());
+
+      job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("finalized");
+    });
+
+    it("second claim fails — bounty already taken", async () => {
+      const { jobPDA } = await postBounty(clientKp);
+      await claimBounty(agentOwnerA, agentProfileA, jobPDA);
+      try {
+        await claimBounty(agentOwnerB, agentProfileB, jobPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidJobStatus");
+      }
+    });
+
+    it("rejects agent missing required capabilities", async () => {
+      const owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      const [profilePDA] = getAgentProfilePDA(owner.publicKey);
+      await program.methods
+        .registerAgent("DocsOnly", "Documentation agent", 0x04, PRICE)
+        .accountsPartial({ owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+      const { jobPDA } = await postBounty(clientKp, 0x02);
+      try {
+        await claimBounty(owner, profilePDA, jobPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "CapabilityMismatch");
+      }
+    });
+
+    it("rejects agent below minimum rating", async () => {
+      const owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      const profilePDA = await registerAgent(owner, "Unrated");
+      const { jobPDA } = await postBounty(clientKp, 0, 400);
+      try {
+        await claimBounty(owner, profilePDA, jobPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "RatingTooLow");
+      }
+    });
+
+    it("rejects claim after the deadline", async () => {
+      const { jobPDA } = await postBounty(clientKp, 0, 0, new BN(1));
+      await sleep(2000);
+      try {
+        await claimBounty(agentOwnerA, agentProfileA, jobPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "BountyExpired");
+      }
+    });
+
+    it("client can cancel an unclaimed bounty for a full refund", async () => {
+      const { jobPDA } = await postBounty(clientKp);
+      const balBefore = await connection.getBalance(clientKp.publicKey);
+      await cancelJob(clientKp, jobPDA);
+      const balAfter = await connection.getBalance(clientKp.publicKey);
+      expect(balAfter).to.be.greaterThan(balBefore + PAYMENT.toNumber() - 10_000);
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Bidding on job requests
+  // ═══════════════════════════════════════
+
+  describe("job request bidding", () => {
+    it("open bidding: accept converts winning bid into a funded job", async () => {
+      const requestPDA = await createJobRequest(clientKp, new BN(3600));
+      const bidA = await submitBid(agentOwnerA, agentProfileA, requestPDA, new BN(LAMPORTS_PER_SOL * 0.8));
+      const bidB = await submitBid(agentOwnerB, agentProfileB, requestPDA, new BN(LAMPORTS_PER_SOL * 1.2));
+
+      const request = await program.account.jobRequest.fetch(requestPDA);
+      expect(request.bidCount).to.equal(2);
+      expect(request.sealed).to.be.false;
+
+      const jobPDA = await nextJobPDA(clientKp.publicKey);
+      await program.methods
+        .acceptBid(null)
+        .accountsPartial({
+          client: clientKp.publicKey,
+          jobRequest: requestPDA,
+          bid: bidA,
+          agent: agentOwnerA.publicKey,
+          agentProfile: agentProfileA,
+          job: jobPDA,
+        })
+        .remainingAccounts([
+          { pubkey: bidB, isWritable: true, isSigner: false },
+          { pubkey: agentOwnerB.publicKey, isWritable: true, isSigner: false },
+        ])
+        .signers([clientKp])
+        .rpc();
+
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.agent.toBase58()).to.equal(agentOwnerA.publicKey.toBase58());
+      expect(job.escrowLamports.toNumber()).to.equal(LAMPORTS_PER_SOL * 0.8);
+      expect(Object.keys(job.status)[0]).to.equal("pending");
+
+      // Request and both bids are closed
+      expect(await connection.getAccountInfo(requestPDA)).to.be.null;
+      expect(await connection.getAccountInfo(bidA)).to.be.null;
+      expect(await connection.getAccountInfo(bidB)).to.be.null;
+    });
+
+    it("rejects open bid above the request budget", async () => {
+      const requestPDA = await createJobRequest(clientKp, new BN(3600), null, PAYMENT);
+      try {
+        await submitBid(agentOwnerA, agentProfileA, requestPDA, new BN(PAYMENT.toNumber() + 1));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "BidExceedsBudget");
+      }
+    });
+
+    it("rejects bids after the bidding deadline", async () => {
+      const requestPDA = await createJobRequest(clientKp, new BN(1));
+      await sleep(2000);
+      try {
+        await submitBid(agentOwnerA, agentProfileA, requestPDA, PAYMENT);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "BiddingClosed");
+      }
+    });
+
+    it("sealed bidding: commit, reveal after deadline, then accept", async () => {
+      const requestPDA = await createJobRequest(clientKp, new BN(2), new BN(3));
+      const price = new BN(LAMPORTS_PER_SOL * 0.7);
+      const eta = new BN(7200);
+      const uri = "ipfs://sealed-proposal";
+      const salt = randomBytes(32);
+
+      const bidPDA = await submitBid(
+        agentOwnerA,
+        agentProfileA,
+        requestPDA,
+        new BN(0),
+        new BN(0),
+        "",
+        bidCommitment(price, eta, uri, salt)
+      );
+      let bid = await program.account.bid.fetch(bidPDA);
+      expect(bid.revealed).to.be.false;
+      expect(bid.priceLamports.toNumber()).to.equal(0);
+
+      const reveal = (saltBytes: Buffer) =>
+        program.methods
+          .revealBid(price, eta, uri, Array.from(saltBytes))
+          .accountsPartial({ agent: agentOwnerA.publicKey, jobRequest: requestPDA, bid: bidPDA })
+          .signers([agentOwnerA])
+          .rpc();
+
+      try {
+        await reveal(salt);
+        expect.fail("Should have thrown — bidding still open");
+      } catch (err: any) {
+        expectAnchorError(err, "BiddingStillOpen");
+      }
+
+      await sleep(2500);
+      try {
+        await reveal(randomBytes(32));
+        expect.fail("Should have thrown — wrong salt");
+      } catch (err: any) {
+        expectAnchorError(err, "BidCommitmentMismatch");
+      }
+      await reveal(salt);
+
+      bid = await program.account.bid.fetch(bidPDA);
+      expect(bid.revealed).to.be.true;
+      expect(bid.priceLamports.toNumber()).to.equal(price.toNumber());
+      expect(bid.proposalUri).to.equal(uri);
+
+      await sleep(3500);
+      const jobPDA = await nextJobPDA(clientKp.publicKey);
+      await program.methods
+        .acceptBid(null)
+        .accountsPartial({
+          client: clientKp.publicKey,
+          jobRequest: requestPDA,
+          bid: bidPDA,
+          agent: agentOwnerA.publicKey,
+          agentProfile: agentProfileA,
+          job: jobPDA,
+        })
+        .signers([clientKp])
+        .rpc();
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.escrowLamports.toNumber()).to.equal(price.toNumber());
+    });
+
+    it("bidder can withdraw a bid with close_bid", async () => {
+      const requestPDA = await createJobRequest(clientKp, new BN(3600));
+      const bidPDA = await submitBid(agentOwnerB, agentProfileB, requestPDA, PAYMENT);
+      await program.methods
+        .closeBid()
+        .accountsPartial({ agent: agentOwnerB.publicKey, bid: bidPDA })
+        .signers([agentOwnerB])
+        .rpc();
+      expect(await connection.getAccountInfo(bidPDA)).to.be.null;
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Subscriptions
+  // ═══════════════════════════════════════
+
+  describe("subscriptions", () => {
+    it("create_subscription escrows every period up front", async () => {
+      const subPDA = await createSubscription(clientKp, agentProfileA, new BN(604_800), 4);
+      const sub = await program.account.subscription.fetch(subPDA);
+      expect(sub.totalPeriods).to.equal(4);
+      expect(sub.claimedPeriods).to.equal(0);
+      expect(sub.agent.toBase58()).to.equal(agentOwnerA.publicKey.toBase58());
+
+      const bal = await connection.getBalance(subPDA);
+      const rent = await connection.getMinimumBalanceForRentExemption(
+        (await connection.getAccountInfo(subPDA))!.data.length
+      );
+      expect(bal).to.equal(PRICE.toNumber() * 4 + rent);
+    });
+
+    it("rejects a claim before the period elapses", async () => {
+      const subPDA = await createSubscription(clientKp, agentProfileA, new BN(3600), 2);
+      try {
+        await claimPeriod(agentOwnerA, agentProfileA, subPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "PeriodNotElapsed");
+      }
+    });
+
+    it("agent claims one period per elapsed interval and stats update", async () => {
+      const subPDA = await createSubscription(clientKp, agentProfileA, new BN(1), 2);
+      await sleep(2000);
+
+      const profileBefore = await program.account.agentProfile.fetch(agentProfileA);
+      const balBefore = await connection.getBalance(agentOwnerA.publicKey);
+      await claimPeriod(agentOwnerA, agentProfileA, subPDA);
+      const balAfter = await connection.getBalance(agentOwnerA.publicKey);
+      expect(balAfter - balBefore).to.be.greaterThan(PRICE.toNumber() - 10_000);
+
+      const sub = await program.account.subscription.fetch(subPDA);
+      expect(sub.claimedPeriods).to.equal(1);
+      const profileAfter = await program.account.agentProfile.fetch(agentProfileA);
+      expect(profileAfter.jobsCompleted).to.equal(profileBefore.jobsCompleted + 1);
+    });
+
+    it("paused subscription stops accruing new periods", async () => {
+      const subPDA = await createSubscription(clientKp, agentProfileA, new BN(2), 3);
+      await program.methods
+        .pauseSubscription()
+        .accountsPartial({ client: clientKp.publicKey, subscription: subPDA })
+        .signers([clientKp])
+        .rpc();
+      await sleep(3000);
+      try {
+        await claimPeriod(agentOwnerA, agentProfileA, subPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "PeriodNotElapsed");
+      }
+
+      await program.methods
+        .resumeSubscription()
+        .accountsPartial({ client: clientKp.publicKey, subscription: subPDA })
+        .signers([clientKp])
+        .rpc();
+      const sub = await program.account.subscription.fetch(subPDA);
+      expect(sub.pausedAt).to.be.null;
+    });
+
+    it("cancel refunds unclaimed periods to the client and closes", async () => {
+      const subPDA = await createSubscription(clientKp, agentProfileB, new BN(3600), 3);
+      const balBefore = await connection.getBalance(clientKp.publicKey);
+      await program.methods
+        .cancelSubscription()
+        .accountsPartial({
+          client: clientKp.publicKey,
+          agentProfile: agentProfileB,
+          agent: agentOwnerB.publicKey,
+          subscription: subPDA,
+        })
+        .signers([clientKp])
+        .rpc();
+      const balAfter = await connection.getBalance(clientKp.publicKey);
+      expect(balAfter - balBefore).to.be.greaterThan(PRICE.toNumber() * 3 - 10_000);
+      expect(await connection.getAccountInfo(subPDA)).to.be.null;
+    });
+
+    it("cancel records the periods already earned on the agent's profile", async () => {
+      const subPDA = await createSubscription(clientKp, agentProfileB, new BN(1), 2);
+      await sleep(3000);
+      const before = await program.account.agentProfile.fetch(agentProfileB);
+      const payoutBefore = await connection.getBalance(agentOwnerB.publicKey);
+      await program.methods
+        .cancelSubscription()
+        .accountsPartial({
+          client: clientKp.publicKey,
+          agentProfile: agentProfileB,
+          agent: agentOwnerB.publicKey,
+          subscription: subPDA,
+        })
+        .signers([clientKp])
+        .rpc();
+      const after = await program.account.agentProfile.fetch(agentProfileB);
+      const paid = (await connection.getBalance(agentOwnerB.publicKey)) - payoutBefore;
+      expect(paid).to.equal(PRICE.toNumber() * 2);
+      expect(after.lifetimeEarnings.sub(before.lifetimeEarnings).toNumber()).to.equal(paid);
+      expect(after.jobsCompleted - before.jobsCompleted).to.equal(2);
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Streaming escrow
+  // ═══════════════════════════════════════
+
+  describe("streaming escrow", () => {
+    const streamWindow = (startOffset: number, duration: number) => {
+      const now = Math.floor(Date.now() / 1000);
+      return {
+        startAt: new BN(now + startOffset),
+        endAt: new BN(now + startOffset + duration),
+      };
+    };
+
+    it("invoke_agent records the vesting schedule", async () => {
+      const stream = streamWindow(0, 3600);
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA, PAYMENT, null, "Stream", { stream });
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.hasExtension).to.be.true;
+      const ext = await program.account.jobExtension.fetch(getJobExtensionPDA(jobPDA)[0]);
+      expect(ext.job.toBase58()).to.equal(jobPDA.toBase58());
+      expect(ext.stream).to.not.be.null;
+      expect(ext.stream!.totalLamports.toNumber()).to.equal(PAYMENT.toNumber());
+      expect(ext.stream!.withdrawnLamports.toNumber()).to.equal(0);
+    });
+
+    it("a plain job is created without an extension", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.hasExtension).to.be.false;
+      expect(await connection.getAccountInfo(getJobExtensionPDA(jobPDA)[0])).to.be.null;
+    });
+
+    it("rejects a stream without its extension account", async () => {
+      const jobPDA = await nextJobPDA(clientKp.publicKey);
+      try {
+        await program.methods
+          .invokeAgent("Stream", PAYMENT, invokeOptions({ stream: streamWindow(0, 3600) }))
+          .accountsPartial({
+            client: clientKp.publicKey,
+            agentProfile: agentProfileA,
+            job: jobPDA,
+            jobExtension: null,
+            agentStake: null,
+          })
+          .signers([clientKp])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "ExtensionMismatch");
+      }
+    });
+
+    it("rejects a stream combined with auto-release", async () => {
+      try {
+        await invokeAgent(clientKp, agentProfileA, PAYMENT, new BN(60), "Stream", {
+          stream: streamWindow(0, 3600),
+        });
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "StreamingJob");
+      }
+    });
+
+    it("agent withdraws vested lamports and the job locks against cancel", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA, PAYMENT, null, "Stream", {
+        stream: streamWindow(-10, 40),
+      });
+      const balBefore = await connection.getBalance(agentOwnerA.publicKey);
+      await withdrawStream(agentOwnerA, agentProfileA, jobPDA);
+      const balAfter = await connection.getBalance(agentOwnerA.publicKey);
+      expect(balAfter).to.be.greaterThan(balBefore);
+
+      const job = await program.account.job.fetch(jobPDA);
+      const { stream } = await program.account.jobExtension.fetch(getJobExtensionPDA(jobPDA)[0]);
+      expect(Object.keys(job.status)[0]).to.equal("inProgress");
+      expect(stream!.withdrawnLamports.toNumber()).to.be.greaterThan(0);
+      expect(job.escrowLamports.toNumber()).to.equal(
+        PAYMENT.toNumber() - stream!.withdrawnLamports.toNumber()
+      );
+    });
+
+    it("fully vested stream finalizes on withdrawal", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA, PAYMENT, null, "Stream", {
+        stream: streamWindow(-10, 12),
+      });
+      await sleep(3000);
+      await withdrawStream(agentOwnerA, agentProfileA, jobPDA);
+      const job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("finalized");
+      expect(job.escrowLamports.toNumber()).to.equal(0);
+    });
+
+    it("client stop_stream refunds the unvested remainder", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileB, PAYMENT, null, "Stream", {
+        stream: streamWindow(3600, 3600),
+      });
+      const balBefore = await connection.getBalance(clientKp.publicKey);
+      await program.methods
+        .stopStream()
+        .accountsPartial({
+          client: clientKp.publicKey,
+          agent: agentOwnerB.publicKey,
+          agentProfile: agentProfileB,
+          job: jobPDA,
+          jobExtension: getJobExtensionPDA(jobPDA)[0],
+        })
+        .signers([clientKp])
+        .rpc();
+      const balAfter = await connection.getBalance(clientKp.publicKey);
+      expect(balAfter - balBefore).to.be.greaterThan(PAYMENT.toNumber() - 10_000);
+
+      const job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("cancelled");
+    });
+
+    it("dispute freezes the stream", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA, PAYMENT, null, "Stream", {
+        stream: streamWindow(-10, 3600),
+      });
+      await raiseDispute(clientKp, jobPDA);
+      try {
+        await withdrawStream(agentOwnerA, agentProfileA, jobPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "StreamFrozen");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Commit-reveal result delivery
+  // ═══════════════════════════════════════
+
+  describe("commit-reveal results", () => {
+    it("commit locks the job against cancellation, reveal completes it", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      const content = "full audit report v1";
+      await commitResult(agentOwnerA, jobPDA, sha256(content));
+
+      let job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("inProgress");
+      expect(job.resultUri).to.equal("");
+
+      try {
+        await cancelJob(clientKp, jobPDA);
+        expect.fail("Should have thrown — escrow is locked");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidJobStatus");
+      }
+
+      await updateJob(agentOwnerA, jobPDA, "ipfs://audit-v1", sha256(content));
+      job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("completed");
+      expect(job.resultUri).to.equal("ipfs://audit-v1");
+    });
+
+    it("reveal with a different hash fails", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await commitResult(agentOwnerA, jobPDA, sha256("original"));
+      try {
+        await updateJob(agentOwnerA, jobPDA, "ipfs://swapped", sha256("swapped"));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "ResultHashMismatch");
+      }
+    });
+
+    it("cannot commit twice", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await commitResult(agentOwnerA, jobPDA, sha256("first"));
+      try {
+        await commitResult(agentOwnerA, jobPDA, sha256("second"));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "ResultAlreadyCommitted");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Encrypted result delivery
+  // ═══════════════════════════════════════
+
+  describe("encrypted results", () => {
+    let encOwner: Keypair;
+    let encProfile: PublicKey;
+    let agentX: ReturnType<typeof x25519Keypair>;
+
+    before(async () => {
+      encOwner = Keypair.generate();
+      await airdrop(encOwner.publicKey);
+      encProfile = await registerAgent(encOwner, "Cipher");
+      agentX = x25519Keypair();
+      await setEncryptionKey(encOwner, encProfile, agentX.raw);
+    });
+
+    it("set_encryption_key publishes the agent's key", async () => {
+      const profile = await program.account.agentProfile.fetch(encProfile);
+      expect(profile.encryptionKey).to.deep.equal(agentX.raw);
+    });
+
+    it("invoke rejects a client key when the agent has none", async () => {
+      try {
+        await invokeAgent(clientKp, agentProfileB, PAYMENT, null, "Secret task", {
+          clientEncryptionKey: x25519Keypair().raw,
+        });
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "EncryptionKeyMissing");
+      }
+    });
+
+    it("only the client can unwrap the delivered content key", async () => {
+      const clientX = x25519Keypair();
+      const { jobPDA } = await invokeAgent(clientKp, encProfile, PAYMENT, null, "Secret task", {
+        clientEncryptionKey: clientX.raw,
+      });
+      const hash = sha256("ciphertext bytes");
+      const contentKey = randomBytes(32);
+      const envelope = sealEnvelope(
+        agentX.privateKey, agentX.raw, clientX.publicKey, clientX.raw, jobPDA, hash, contentKey
+      );
+      await updateJob(encOwner, jobPDA, "ipfs://encrypted", hash, envelope);
+
+      const job = await program.account.job.fetch(jobPDA);
+      const ext = await program.account.jobExtension.fetch(getJobExtensionPDA(jobPDA)[0]);
+      expect(ext.clientEncryptionKey).to.deep.equal(clientX.raw);
+      expect(ext.resultEnvelope.agentKey).to.deep.equal(agentX.raw);
+
+      // Client side: ECDH against the agent key named in the envelope
+      const shared = diffieHellman({
+        privateKey: clientX.privateKey,
+        publicKey: agentX.publicKey,
+      });
+      const wrapped = Buffer.from(ext.resultEnvelope.wrappedKey);
+      const decipher = createDecipheriv(
+        "chacha20-poly1305",
+        deriveWrapKey(shared, jobPDA, clientX.raw, ext.resultEnvelope.agentKey),
+        Buffer.from(ext.resultEnvelope.nonce),
+        { authTagLength: 16 }
+      );
+      decipher.setAAD(envelopeAad(jobPDA, job.resultHash), { plaintextLength: 32 });
+      decipher.setAuthTag(wrapped.subarray(32));
+      const unwrapped = Buffer.concat([decipher.update(wrapped.subarray(0, 32)), decipher.final()]);
+      expect(unwrapped.equals(contentKey)).to.be.true;
+    });
+
+    it("rejects a plaintext delivery when the client asked for encryption", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, encProfile, PAYMENT, null, "Secret task", {
+        clientEncryptionKey: x25519Keypair().raw,
+      });
+      try {
+        await updateJob(encOwner, jobPDA, "ipfs://plaintext");
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "EnvelopeRequired");
+      }
+    });
+
+    it("rejects an envelope made with a key the agent never published", async () => {
+      const clientX = x25519Keypair();
+      const rogue = x25519Keypair();
+      const { jobPDA } = await invokeAgent(clientKp, encProfile, PAYMENT, null, "Secret task", {
+        clientEncryptionKey: clientX.raw,
+      });
+      const hash = sha256("ciphertext");
+      const envelope = sealEnvelope(
+        rogue.privateKey, rogue.raw, clientX.publicKey, clientX.raw, jobPDA, hash, randomBytes(32)
+      );
+      try {
+        await updateJob(encOwner, jobPDA, "ipfs://encrypted", hash, envelope);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidEnvelope");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Off-chain job specs
+  // ═══════════════════════════════════════
+
+  describe("off-chain specs", () => {
+    const specDoc = "# Audit spec\n\nReview every instruction handler for missing signer checks.";
+    const spec: JobSpec = { uri: "ipfs://QmAuditSpec", hash: sha256(specDoc) };
+
+    it("stores a spec URI + hash the agent can verify", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA, PAYMENT, null, "", { spec });
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.title).to.equal("");
+      expect(job.spec.uri).to.equal(spec.uri);
+      // Agent side: the fetched document must hash to what the client escrowed for
+      expect(sha256(specDoc)).to.deep.equal(job.spec.hash);
+      expect(sha256(specDoc + " and drain the vault")).to.not.deep.equal(job.spec.hash);
+    });
+
+    it("delegation carries its own spec to the child job", async () => {
+      const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
+      const childSpec: JobSpec = { uri: "ipfs://QmSubSpec", hash: sha256("sub-spec") };
+      const { childJobPDA } = await delegateTask(
+        agentOwnerA, parentPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 10), "Reentrancy pass", childSpec
+      );
+      const child = await program.account.job.fetch(childJobPDA);
+      expect(child.title).to.equal("Reentrancy pass");
+      expect(child.spec.hash).to.deep.equal(childSpec.hash);
+    });
+
+    it("rejects titles over 64 bytes", async () => {
+      try {
+        await invokeAgent(clientKp, agentProfileA, PAYMENT, null, "x".repeat(65));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "TitleTooLong");
+      }
+    });
+
+    it("rejects a spec without a hash", async () => {
+      try {
+        await invokeAgent(clientKp, agentProfileA, PAYMENT, null, "Audit", {
+          spec: { uri: "ipfs://QmAuditSpec", hash: new Array(32).fill(0) },
+        });
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidSpec");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Job nonce counters
+  // ═══════════════════════════════════════
+
+  describe("job counters", () => {
+    it("back-to-back invocations get sequential nonces", async () => {
+      const client = Keypair.generate();
+      await airdrop(client.publicKey);
+
+      // Same client, same agent, same second: timestamp seeds used to collide here
+      const a = await invokeAgent(client, agentProfileA);
+      const c = await invokeAgent(client, agentProfileA);
+      expect(a.nonce.toNumber()).to.equal(0);
+      expect(c.nonce.toNumber()).to.equal(1);
+
+      const counter = await program.account.jobCounter.fetch(getJobCounterPDA(client.publicKey)[0]);
+      expect(counter.authority.toBase58()).to.equal(client.publicKey.toBase58());
+      expect(counter.nextNonce.toNumber()).to.equal(2);
+      const job = await program.account.job.fetch(c.jobPDA);
+      expect(job.nonce.toNumber()).to.equal(1);
+    });
+
+    it("enumerates every job a creator opened, across job kinds", async () => {
+      const client = Keypair.generate();
+      await airdrop(client.publicKey);
+      const { jobPDA: invoked } = await invokeAgent(client, agentProfileA);
+      const { jobPDA: bounty } = await postBounty(client);
+      const jobs = await listJobPDAs(client.publicKey);
+      expect(jobs.map((k) => k.toBase58())).to.deep.equal([invoked.toBase58(), bounty.toBase58()]);
+
+      const fetched = await program.account.job.fetchMultiple(jobs);
+      expect(fetched.every((j) => j !== null && j.client.equals(client.publicKey))).to.be.true;
+    });
+
+    it("delegations draw from the delegating agent's own counter", async () => {
+      const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
+      const before = await nextJobNonce(agentOwnerA.publicKey);
+      const { childJobPDA, nonce } = await delegateTask(
+        agentOwnerA, parentPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 10)
+      );
+      expect(nonce.toString()).to.equal(before.toString());
+      expect(childJobPDA.toBase58()).to.equal(getJobPDA(agentOwnerA.publicKey, before)[0].toBase58());
+      expect((await nextJobNonce(agentOwnerA.publicKey)).toNumber()).to.equal(before.toNumber() + 1);
+    });
+
+    it("job requests and subscriptions take nonces from the same counter", async () => {
+      const client = Keypair.generate();
+      await airdrop(client.publicKey);
+      const requestPDA = await createJobRequest(client, new BN(3600));
+      const subPDA = await createSubscription(client, agentProfileA, new BN(3600), 1);
+      const { jobPDA } = await invokeAgent(client, agentProfileA);
+
+      expect((await program.account.jobRequest.fetch(requestPDA)).nonce.toNumber()).to.equal(0);
+      expect((await program.account.subscription.fetch(subPDA)).nonce.toNumber()).to.equal(1);
+      expect((await program.account.job.fetch(jobPDA)).nonce.toNumber()).to.equal(2);
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Multi-agent team jobs
+  // ═══════════════════════════════════════
+
+  describe("team jobs", () => {
+    it("lead completes and release splits escrow by share", async () => {
+      const { jobPDA, teamPDA } = await invokeTeam(clientKp, agentProfileA, [agentProfileB], [6_000, 4_000]);
+      const team = await program.account.jobTeam.fetch(teamPDA);
+      expect(team.members.map((m) => m.shareBps)).to.deep.equal([6_000, 4_000]);
+      expect((await program.account.job.fetch(jobPDA)).teamSize).to.equal(2);
+
+      await updateJob(agentOwnerA, jobPDA);
+      const beforeA = await connection.getBalance(agentOwnerA.publicKey);
+      const beforeB = await connection.getBalance(agentOwnerB.publicKey);
+      const jobsB = (await program.account.agentProfile.fetch(agentProfileB)).jobsCompleted;
+
+      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA, null, {
+        team: teamPDA,
+        members: [{ profile: agentProfileB, wallet: agentOwnerB.publicKey }],
+      });
+
+      expect((await connection.getBalance(agentOwnerA.publicKey)) - beforeA).to.equal(1.2 * LAMPORTS_PER_SOL);
+      expect((await connection.getBalance(agentOwnerB.publicKey)) - beforeB).to.equal(0.8 * LAMPORTS_PER_SOL);
+      expect((await program.account.agentProfile.fetch(agentProfileB)).jobsCompleted).to.equal(jobsB + 1);
+      expect(await connection.getAccountInfo(teamPDA)).to.be.null;
+    });
+
+    it("all-sign-off mode completes only after every member signs", async () => {
+      const { jobPDA, teamPDA } = await invokeTeam(clientKp, agentProfileA, [agentProfileB], [5_000, 5_000], true);
+      await updateJob(agentOwnerA, jobPDA);
+      let job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("inProgress");
+      expect(job.pendingSignoffs).to.equal(1);
+
+      try {
+        await updateJob(agentOwnerA, jobPDA, "https://swapped.example.com");
+        expect.fail("Lead cannot rewrite a delivery awaiting sign-off");
+      } catch (err: any) {
+        expectAnchorError(err, "ResultAlreadyCommitted");
+      }
+
+      try {
+        await program.methods
+          .signOffResult()
+          .accountsPartial({ member: agentOwnerA.publicKey, memberProfile: agentProfileA, job: jobPDA })
+          .signers([agentOwnerA])
+          .rpc();
+        expect.fail("Lead's delivery is its sign-off");
+      } catch (err: any) {
+        expectAnchorError(err, "NotTeamMember");
+      }
+
+      await program.methods
+        .signOffResult()
+        .accountsPartial({ member: agentOwnerB.publicKey, memberProfile: agentProfileB, job: jobPDA })
+        .signers([agentOwnerB])
+        .rpc();
+      job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("completed");
+      expect((await program.account.jobTeam.fetch(teamPDA)).members[1].signedOff).to.be.true;
+    });
+
+    it("the lead's delegation comes out of its own share", async () => {
+      const { jobPDA, teamPDA } = await invokeTeam(clientKp, agentProfileA, [agentProfileB], [6_000, 4_000]);
+      // Lead share is 1.2 SOL of the 2 SOL escrow
+      const { childJobPDA } = await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 2));
+      try {
+        await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(0.8 * LAMPORTS_PER_SOL));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InsufficientEscrow");
+      }
+
+      await updateJob(agentOwnerB, childJobPDA);
+      await releasePayment(agentOwnerA, agentOwnerB.publicKey, agentProfileB, childJobPDA, jobPDA);
+      await updateJob(agentOwnerA, jobPDA);
+
+      const beforeA = await connection.getBalance(agentOwnerA.publicKey);
+      const beforeB = await connection.getBalance(agentOwnerB.publicKey);
+      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA, null, {
+        team: teamPDA,
+        members: [{ profile: agentProfileB, wallet: agentOwnerB.publicKey }],
+      });
+      expect((await connection.getBalance(agentOwnerA.publicKey)) - beforeA).to.equal(0.7 * LAMPORTS_PER_SOL);
+      expect((await connection.getBalance(agentOwnerB.publicKey)) - beforeB).to.equal(0.8 * LAMPORTS_PER_SOL);
+    });
+
+    it("cancelling a team job closes its roster", async () => {
+      const { jobPDA, teamPDA } = await invokeTeam(clientKp, agentProfileA, [agentProfileB], [5_000, 5_000]);
+      await cancelJob(clientKp, jobPDA);
+      expect(await connection.getAccountInfo(jobPDA)).to.be.null;
+      expect(await connection.getAccountInfo(teamPDA)).to.be.null;
+    });
+
+    it("rejects shares that do not sum to 100%", async () => {
+      try {
+        await invokeTeam(clientKp, agentProfileA, [agentProfileB], [5_000, 4_000]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidTeamShares");
+      }
+    });
+
+    it("release without the team's accounts fails", async () => {
+      const { jobPDA, teamPDA } = await invokeTeam(clientKp, agentProfileA, [agentProfileB], [5_000, 5_000]);
+      await updateJob(agentOwnerA, jobPDA);
+      try {
+        await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA, null, {
+          team: teamPDA,
+          members: [],
+        });
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "TeamMismatch");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Batched invocation
+  // ═══════════════════════════════════════
+
+  describe("batched invocation", () => {
+    it("opens one job per entry with a shared group ID", async () => {
+      const client = Keypair.generate();
+      await airdrop(client.publicKey);
+      const jobs = await invokeAgentsBatch(client, [
+        { profile: agentProfileA, title: "Frontend review", payment: PAYMENT },
+        { profile: agentProfileB, title: "Backend review", payment: PAYMENT.muln(2) },
+        { profile: agentProfileA, title: "Docs pass", payment: PRICE },
+      ]);
+
+      const fetched = await program.account.job.fetchMultiple(jobs);
+      expect(fetched.map((j) => j.nonce.toNumber())).to.deep.equal([0, 1, 2]);
+      expect(fetched.every((j) => j.groupId.equals(jobs[0]))).to.be.true;
+      expect(fetched[1].agent.toBase58()).to.equal(agentOwnerB.publicKey.toBase58());
+      expect(fetched[1].escrowLamports.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+      expect(Object.keys(fetched[2].status)[0]).to.equal("pending");
+
+      const rent = await connection.getMinimumBalanceForRentExemption(
+        (await connection.getAccountInfo(jobs[0])).data.length
+      );
+      expect(await connection.getBalance(jobs[0])).to.equal(rent + PAYMENT.toNumber());
+    });
+
+    it("batch jobs follow the normal completion flow", async () => {
+      const [jobPDA] = await invokeAgentsBatch(clientKp, [
+        { profile: agentProfileA, title: "Solo entry", payment: PAYMENT },
+      ]);
+      await updateJob(agentOwnerA, jobPDA);
+      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
+      const job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("finalized");
+    });
+
+    it("opens a job whose PDA was pre-funded by a third party", async () => {
+      const client = Keypair.generate();
+      await airdrop(client.publicKey);
+      const [nextJob] = getJobPDA(client.publicKey, await nextJobNonce(client.publicKey));
+      await airdrop(nextJob, 1_000_000);
+
+      const [jobPDA] = await invokeAgentsBatch(client, [
+        { profile: agentProfileA, title: "Griefed", payment: PAYMENT },
+      ]);
+      expect(jobPDA.toBase58()).to.equal(nextJob.toBase58());
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.escrowLamports.toNumber()).to.equal(PAYMENT.toNumber());
+
+      const rent = await connection.getMinimumBalanceForRentExemption(
+        (await connection.getAccountInfo(jobPDA)).data.length
+      );
+      expect(await connection.getBalance(jobPDA)).to.equal(rent + PAYMENT.toNumber());
+    });
+
+    it("rejects a job account that is not the next nonce PDA", async () => {
+      try {
+        await invokeAgentsBatch(
+          clientKp,
+          [{ profile: agentProfileA, title: "Bad PDA", payment: PAYMENT }],
+          [Keypair.generate().publicKey]
+        );
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidBatch");
+      }
+    });
+
+    it("rejects an entry paying below the agent's price", async () => {
+      try {
+        await invokeAgentsBatch(clientKp, [
+          { profile: agentProfileA, title: "Fine", payment: PAYMENT },
+          { profile: agentProfileB, title: "Underpaid", payment: new BN(1000) },
+        ]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InsufficientPayment");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Release bonuses
+  // ═══════════════════════════════════════
+
+  describe("release bonuses", () => {
+    it("pays the bonus on top of escrow and records it", async () => {
+      const bonus = new BN(0.25 * LAMPORTS_PER_SOL);
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      const before = await connection.getBalance(agentOwnerA.publicKey);
+      const earningsBefore = (await program.account.agentProfile.fetch(agentProfileA)).lifetimeEarnings;
+
+      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA, null, null, bonus);
+
+      const paid = PAYMENT.add(bonus).toNumber();
+      expect((await connection.getBalance(agentOwnerA.publicKey)) - before).to.equal(paid);
+      expect((await program.account.job.fetch(jobPDA)).bonusLamports.toNumber()).to.equal(bonus.toNumber());
+      const profile = await program.account.agentProfile.fetch(agentProfileA);
+      expect(profile.lifetimeEarnings.sub(earningsBefore).toNumber()).to.equal(paid);
+
+      // Job PDA keeps only rent — the bonus passed straight through
+      const info = await connection.getAccountInfo(jobPDA);
+      const rent = await connection.getMinimumBalanceForRentExemption(info!.data.length);
+      expect(info!.lamports).to.equal(rent);
+    });
+
+    it("splits a team bonus by share", async () => {
+      const { jobPDA, teamPDA } = await invokeTeam(clientKp, agentProfileA, [agentProfileB], [7_500, 2_500]);
+      await updateJob(agentOwnerA, jobPDA);
+      const beforeB = await connection.getBalance(agentOwnerB.publicKey);
+      const earningsB = (await program.account.agentProfile.fetch(agentProfileB)).lifetimeEarnings;
+
+      await releasePayment(
+        clientKp,
+        agentOwnerA.publicKey,
+        agentProfileA,
+        jobPDA,
+        null,
+        { team: teamPDA, members: [{ profile: agentProfileB, wallet: agentOwnerB.publicKey }] },
+        new BN(LAMPORTS_PER_SOL)
+      );
+
+      // 25% of the 2 SOL escrow plus 25% of the 1 SOL bonus
+      const expected = 0.75 * LAMPORTS_PER_SOL;
+      expect((await connection.getBalance(agentOwnerB.publicKey)) - beforeB).to.equal(expected);
+      const profileB = await program.account.agentProfile.fetch(agentProfileB);
+      expect(profileB.lifetimeEarnings.sub(earningsB).toNumber()).to.equal(expected);
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Partial release
+  // ═══════════════════════════════════════
+
+  describe("partial release", () => {
+    it("agent accepts the client's split and the job finalizes", async () => {
+      const agentShare = new BN(0.6 * LAMPORTS_PER_SOL);
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      await proposePartialRelease(clientKp, jobPDA, agentShare);
+
+      const agentBefore = await connection.getBalance(agentOwnerA.publicKey);
+      const clientBefore = await connection.getBalance(clientKp.publicKey);
+      await acceptPartialRelease(agentOwnerA, agentProfileA, jobPDA, clientKp.publicKey, agentShare);
+
+      // Agent pays the fee, so only the client's delta is exact
+      expect((await connection.getBalance(clientKp.publicKey)) - clientBefore).to.equal(0.4 * LAMPORTS_PER_SOL);
+      expect((await connection.getBalance(agentOwnerA.publicKey)) - agentBefore).to.be.greaterThan(
+        agentShare.toNumber() - 10_000
+      );
+      const job = await program.account.job.fetch(jobPDA);
+      expect(Object.keys(job.status)[0]).to.equal("finalized");
+      expect(job.escrowLamports.toNumber()).to.equal(0);
+    });
+
+    it("rejects an acceptance that does not match the proposal", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      await proposePartialRelease(clientKp, jobPDA, new BN(0.5 * LAMPORTS_PER_SOL));
+      try {
+        await acceptPartialRelease(
+          agentOwnerA, agentProfileA, jobPDA, clientKp.publicKey, new BN(0.9 * LAMPORTS_PER_SOL)
+        );
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "PartialReleaseMismatch");
+      }
+    });
+
+    it("rejects acceptance without a proposal", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      try {
+        await acceptPartialRelease(agentOwnerA, agentProfileA, jobPDA, clientKp.publicKey, PAYMENT);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "NoPartialReleaseProposal");
+      }
+    });
+
+    it("rejects a proposal above the escrow", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      try {
+        await proposePartialRelease(clientKp, jobPDA, PAYMENT.addn(1));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InsufficientEscrow");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Job reassignment
+  // ═══════════════════════════════════════
+
+  describe("job reassignment", () => {
+    it("moves an undelivered job to another agent who can complete it", async () => {
+      const { jobPDA, nonce } = await invokeAgent(clientKp, agentProfileA);
+      await reassignJob(clientKp, jobPDA, agentProfileB);
+
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.agent.toBase58()).to.equal(agentOwnerB.publicKey.toBase58());
+      expect(job.nonce.toNumber()).to.equal(nonce.toNumber());
+      expect(Object.keys(job.status)[0]).to.equal("pending");
+
+      try {
+        await updateJob(agentOwnerA, jobPDA);
+        expect.fail("Previous agent can no longer deliver");
+      } catch (err: any) {
+        expectAnchorError(err, "Unauthorized");
+      }
+
+      await updateJob(agentOwnerB, jobPDA);
+      const before = await connection.getBalance(agentOwnerB.publicKey);
+      await releasePayment(clientKp, agentOwnerB.publicKey, agentProfileB, jobPDA);
+      expect((await connection.getBalance(agentOwnerB.publicKey)) - before).to.equal(PAYMENT.toNumber());
+    });
+
+    it("rejects reassignment once a result is delivered", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      try {
+        await reassignJob(clientKp, jobPDA, agentProfileB);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidJobStatus");
+      }
+    });
+
+    it("re-checks the client's stake requirement against the new agent", async () => {
+      const requirement = { minLamports: new BN(LAMPORTS_PER_SOL), slashBps: 2_500 };
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      try {
+        await reassignJob(clientKp, jobPDA, agentProfileB, requirement);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InsufficientStake");
+      }
+
+      const owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      const profilePDA = await registerAgent(owner, "Staked backup");
+      await stakeAgent(owner, profilePDA, new BN(LAMPORTS_PER_SOL));
+      await reassignJob(clientKp, jobPDA, profilePDA, requirement);
+      expect((await program.account.job.fetch(jobPDA)).slashBps).to.equal(2_500);
+    });
+
+    it("rejects reassignment after a result commit", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await commitResult(agentOwnerA, jobPDA, sha256("committed"));
+      try {
+        await reassignJob(clientKp, jobPDA, agentProfileB);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "ResultAlreadyCommitted");
+      }
+    });
+
+    it("rejects a non-client signer", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      try {
+        await reassignJob(agentOwnerB, jobPDA, agentProfileB);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "Unauthorized");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Cascading cancellation
+  // ═══════════════════════════════════════
+
+  describe("cascading cancellation", () => {
+    it("refunds pending children into the parent and the parent to the client", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      const { childJobPDA: child1 } = await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 4));
+      const { childJobPDA: child2 } = await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 5));
+
+      const clientBefore = await connection.getBalance(clientKp.publicKey);
+      const agentBefore = await connection.getBalance(agentOwnerA.publicKey);
+      const parentRent = await connection.getBalance(jobPDA) - (await program.account.job.fetch(jobPDA)).escrowLamports.toNumber();
+      const childRent = await connection.getMinimumBalanceForRentExemption(
+        (await connection.getAccountInfo(child1))!.data.length
+      );
+
+      await cancelJobTree(clientKp, jobPDA, [child1, child2]);
+
+      // Client gets the full original escrow back plus the parent's rent, minus the fee
+      const clientDelta = (await connection.getBalance(clientKp.publicKey)) - clientBefore;
+      expect(clientDelta).to.be.greaterThan(PAYMENT.toNumber() + parentRent - 10_000);
+      expect(clientDelta).to.be.at.most(PAYMENT.toNumber() + parentRent);
+      // The delegating agent recovers the rent it paid for both children
+      expect((await connection.getBalance(agentOwnerA.publicKey)) - agentBefore).to.equal(2 * childRent);
+      for (const pda of [jobPDA, child1, child2]) {
+        expect(await connection.getAccountInfo(pda)).to.be.null;
+      }
+    });
+
+    it("returns a cancelled subtree's escrow to the job it was delegated from", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      const { childJobPDA } = await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 4));
+      const { childJobPDA: grandchild } = await delegateTask(
+        agentOwnerB, childJobPDA, agentProfileA, new BN(LAMPORTS_PER_SOL / 10)
+      );
+
+      await cancelJobTree(agentOwnerA, childJobPDA, [grandchild]);
+
+      const parent = await program.account.job.fetch(jobPDA);
+      expect(parent.activeChildren).to.equal(0);
+      expect(parent.delegatedTotal.toNumber()).to.equal(0);
+      expect(parent.escrowLamports.toNumber()).to.equal(PAYMENT.toNumber());
+      for (const pda of [childJobPDA, grandchild]) {
+        expect(await connection.getAccountInfo(pda)).to.be.null;
+      }
+    });
+
+    it("requires every active child", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      const { childJobPDA: child1 } = await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 4));
+      await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 4));
+      try {
+        await cancelJobTree(clientKp, jobPDA, [child1]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "UnresolvedChildren");
+      }
+    });
+
+    it("rejects a child the sub-agent has already delivered", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      const { childJobPDA } = await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 4));
+      await updateJob(agentOwnerB, childJobPDA);
+      try {
+        await cancelJobTree(clientKp, jobPDA, [childJobPDA]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidJobStatus");
+      }
+    });
+
+    it("rejects a job that is not the parent's child", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 4));
+      const { jobPDA: unrelated } = await invokeAgent(clientKp, agentProfileB);
+      try {
+        await cancelJobTree(clientKp, jobPDA, [unrelated]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "ParentJobMismatch");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Delegation policy
+  // ═══════════════════════════════════════
+
+  describe("delegation policy", () => {
+    const policy = (overrides: Partial<DelegationPolicy> = {}): InvokeOpts => ({
+      delegationPolicy: {
+        allowDelegation: true,
+        maxShareBps: 10_000,
+        allowedSubAgents: [],
+        minSubAgentRatingX100: 0,
+        ...overrides,
+      },
+    });
+
+    it("rejects delegate_task when the client disallows delegation", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA, PAYMENT, null, "No subcontracting", policy({ allowDelegation: false }));
+      try {
+        await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 10));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "DelegationNotAllowed");
+      }
+    });
+
+    it("caps the combined share of all child jobs", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA, PAYMENT, null, "Capped", policy({ maxShareBps: 5_000 }));
+      await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(0.3 * LAMPORTS_PER_SOL));
+      try {
+        // 0.3 + 0.3 SOL exceeds 50% of the 1 SOL escrow
+        await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(0.3 * LAMPORTS_PER_SOL));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "DelegationShareExceeded");
+      }
+      await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(0.2 * LAMPORTS_PER_SOL));
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.delegatedTotal.toNumber()).to.equal(0.5 * LAMPORTS_PER_SOL);
+    });
+
+    it("only allows listed sub-agents", async () => {
+      const { jobPDA } = await invokeAgent(
+        clientKp, agentProfileA, PAYMENT, null, "Allowlisted", policy({ allowedSubAgents: [agentProfileA] })
+      );
+      try {
+        await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 10));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "SubAgentNotAllowed");
+      }
+    });
+
+    it("enforces a minimum sub-agent rating", async () => {
+      const { jobPDA } = await invokeAgent(
+        clientKp, agentProfileA, PAYMENT, null, "Rated only", policy({ minSubAgentRatingX100: 501 })
+      );
+      try {
+        await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 10));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "SubAgentRatingTooLow");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Child result aggregation
+  // ═══════════════════════════════════════
+
+  describe("child result aggregation", () => {
+    const foldChild = (acc: Buffer, child: PublicKey, resultHash: number[]) =>
+      createHash("sha256").update(Buffer.concat([acc, child.toBuffer(), Buffer.from(resultHash)])).digest();
+
+    it("folds each released child's result into the parent", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      const { childJobPDA: child1 } = await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 4));
+      const { childJobPDA: child2 } = await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(LAMPORTS_PER_SOL / 4));
+
+      await updateJob(agentOwnerB, child2, "https://child-2.example.com");
+      await releasePayment(agentOwnerA, agentOwnerB.publicKey, agentProfileB, child2, jobPDA);
+      await updateJob(agentOwnerB, child1, "https://child-1.example.com");
+      await releasePayment(agentOwnerA, agentOwnerB.publicKey, agentProfileB, child1, jobPDA);
+      await updateJob(agentOwnerA, jobPDA);
+
+      // Folded in release order
+      let expected = foldChild(Buffer.alloc(32), child2, sha256("https://child-2.example.com"));
+      expected = foldChild(expected, child1, sha256("https://child-1.example.com"));
+
+      const parent = await program.account.job.fetch(jobPDA);
+      expect(parent.childrenCompleted).to.equal(2);
+      expect(Buffer.from(parent.childResultsHash).equals(expected)).to.be.true;
+    });
+
+    it("leaves the aggregate zero for jobs without children", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      await updateJob(agentOwnerA, jobPDA);
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.childrenCompleted).to.equal(0);
+      expect(job.childResultsHash).to.deep.equal(new Array(32).fill(0));
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Delegation margin accounting
+  // ═══════════════════════════════════════
+
+  describe("delegation margin accounting", () => {
+    it("tracks original escrow and reports the parent's margin on release", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      const { childJobPDA } = await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(0.3 * LAMPORTS_PER_SOL));
+      await updateJob(agentOwnerB, childJobPDA);
+      await releasePayment(agentOwnerA, agentOwnerB.publicKey, agentProfileB, childJobPDA, jobPDA);
+      await updateJob(agentOwnerA, jobPDA);
+
+      let job = await program.account.job.fetch(jobPDA);
+      expect(job.originalEscrow.toNumber()).to.equal(PAYMENT.toNumber());
+      expect(job.delegatedTotal.toNumber()).to.equal(0.3 * LAMPORTS_PER_SOL);
+
+      const sig = await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
+      const tx = await connection.getTransaction(sig, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+      const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+      const released = [...parser.parseLogs(tx?.meta?.logMessages || [])].find((e) => e.name === "paymentReleased");
+      expect(released.data.marginLamports.toNumber()).to.equal(0.7 * LAMPORTS_PER_SOL);
+
+      // original_escrow = delegated_total + margin
+      job = await program.account.job.fetch(jobPDA);
+      expect(job.delegatedTotal.add(released.data.marginLamports).eq(job.originalEscrow)).to.be.true;
+    });
+
+    it("on team jobs the margin is the lead's payout after member shares", async () => {
+      const { jobPDA, teamPDA } = await invokeTeam(clientKp, agentProfileA, [agentProfileB], [6_000, 4_000]);
+      const { childJobPDA } = await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(0.5 * LAMPORTS_PER_SOL));
+      await updateJob(agentOwnerB, childJobPDA);
+      await releasePayment(agentOwnerA, agentOwnerB.publicKey, agentProfileB, childJobPDA, jobPDA);
+      await updateJob(agentOwnerA, jobPDA);
+
+      const sig = await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA, null, {
+        team: teamPDA,
+        members: [{ profile: agentProfileB, wallet: agentOwnerB.publicKey }],
+      });
+      const tx = await connection.getTransaction(sig, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+      const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+      const released = [...parser.parseLogs(tx?.meta?.logMessages || [])].filter((e) => e.name === "paymentReleased");
+      expect(released.map((e) => e.data.marginLamports?.toNumber() ?? null)).to.deep.equal([
+        null, // member B
+        0.7 * LAMPORTS_PER_SOL, // lead: 1.2 SOL share - 0.5 SOL delegated
+      ]);
+    });
+
+    it("child jobs record their own original escrow", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      const { childJobPDA } = await delegateTask(agentOwnerA, jobPDA, agentProfileB, new BN(0.2 * LAMPORTS_PER_SOL));
+      const child = await program.account.job.fetch(childJobPDA);
+      expect(child.originalEscrow.toNumber()).to.equal(0.2 * LAMPORTS_PER_SOL);
+      expect(child.delegatedTotal.toNumber()).to.equal(0);
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Agent capacity
+  // ═══════════════════════════════════════
+
+  describe("agent capacity", () => {
+    let owner: Keypair;
+    let profilePDA: PublicKey;
+
+    before(async () => {
+      owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      profilePDA = await registerAgent(owner, "CapacityAgent", PRICE);
+      await setMaxConcurrentJobs(owner, profilePDA, 2);
+    });
+
+    it("rejects new work once the agent is at capacity", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, profilePDA);
+      await invokeAgent(clientKp, profilePDA);
+      expect((await program.account.agentProfile.fetch(profilePDA)).activeJobs).to.equal(2);
+
+      try {
+        await invokeAgent(clientKp, profilePDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "AgentAtCapacity");
+      }
+
+      // Finishing a job frees its slot
+      await updateJob(owner, jobPDA);
+      await releasePayment(clientKp, owner.publicKey, profilePDA, jobPDA);
+      expect((await program.account.agentProfile.fetch(profilePDA)).activeJobs).to.equal(1);
+      const { jobPDA: next } = await invokeAgent(clientKp, profilePDA);
+
+      await cancelJob(clientKp, next);
+      expect((await program.account.agentProfile.fetch(profilePDA)).activeJobs).to.equal(1);
+    });
+
+    it("counts delegated child jobs against the sub-agent", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+      // One job is still open from the previous test; this fills the second slot
+      await invokeAgent(clientKp, profilePDA);
+      try {
+        await delegateTask(agentOwnerA, jobPDA, profilePDA, new BN(LAMPORTS_PER_SOL / 10));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "AgentAtCapacity");
+      }
+    });
+
+    it("only the owner can change the limit", async () => {
+      try {
+        await setMaxConcurrentJobs(agentOwnerA, profilePDA, 0);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "Unauthorized");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════
+  //  Capability pricing
+  // ═══════════════════════════════════════
+
+  describe("capability pricing", () => {
+    const CAP_SECURITY_AUDIT = 1 << 1;
+    const CAP_DOCUMENTATION = 1 << 2;
+    let owner: Keypair;
+    let profilePDA: PublicKey;
+
+    before(async () => {
+      owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      profilePDA = await registerAgent(owner, "PricedAgent");
+      await setPriceTable(owner, profilePDA, [
+        { capability: CAP_SECURITY_AUDIT, tier: 0, priceLamports: new BN(2 * LAMPORTS_PER_SOL) },
+        { capability: CAP_SECURITY_AUDIT, tier: 2, priceLamports: new BN(4 * LAMPORTS_PER_SOL) },
+        { capability: CAP_DOCUMENTATION, tier: 0, priceLamports: new BN(0.2 * LAMPORTS_PER_SOL) },
+      ]);
+    });
+
+    it("charges the capability's price instead of the default", async () => {
+      // Documentation is cheaper than the 0.5 SOL default
+      await invokeAgent(clientKp, profilePDA, new BN(0.2 * LAMPORTS_PER_SOL), null, "Docs", {
+        capabilities: CAP_DOCUMENTATION,
+      });
+      try {
+        await invokeAgent(clientKp, profilePDA, PAYMENT, null, "Audit", { capabilities: CAP_SECURITY_AUDIT });
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InsufficientPayment");
+      }
+    });
+
+    it("uses the tier's price, falling back to the capability's base tier", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, profilePDA, new BN(2 * LAMPORTS_PER_SOL), null, "Small audit", {
+        capabilities: CAP_SECURITY_AUDIT,
+        priceTier: 1,
+      });
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.requiredCapabilities).to.equal(CAP_SECURITY_AUDIT);
+      expect(job.priceTier).to.equal(1);
+      try {
+        await invokeAgent(clientKp, profilePDA, new BN(2 * LAMPORTS_PER_SOL), null, "Large audit", {
+          capabilities: CAP_SECURITY_AUDIT,
+          priceTier: 2,
+        });
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InsufficientPayment");
+      }
+    });
+
+    it("rejects a capability the agent does not offer", async () => {
+      try {
+        // Test agents offer CAP_CODE_REVIEW..CAP_GENERAL (0x3f)
+        await invokeAgent(clientKp, profilePDA, PAYMENT, null, "Unknown", { capabilities: 1 << 6 });
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "CapabilityMismatch");
+      }
+    });
+
+    it("rejects duplicate price table entries", async () => {
+      try {
+        await setPriceTable(owner, profilePDA, [
+          { capability: CAP_DOCUMENTATION, tier: 0, priceLamports: PRICE },
+          { capability: CAP_DOCUMENTATION, tier: 0, priceLamports: PAYMENT },
+        ]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidPriceTable");
       }
     });
   });
 
   // ═══════════════════════════════════════
-  //  cancel_job
+  //  Payout wallet and operator key
   // ═══════════════════════════════════════
 
-  describe("cancel_job", () => {
-    it("cancels Pending job and refunds client", async () => {
-      const balBefore = await connection.getBalance(clientKp.publicKey);
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
-      const balAfterInvoke = await connection.getBalance(clientKp.publicKey);
+  describe("payout wallet and operator key", () => {
+    let owner: Keypair;
+    let operator: Keypair;
+    let cold: Keypair;
+    let profilePDA: PublicKey;
 
-      await cancelJob(clientKp, jobPDA);
-      const balAfterCancel = await connection.getBalance(clientKp.publicKey);
+    before(async () => {
+      owner = Keypair.generate();
+      operator = Keypair.generate();
+      cold = Keypair.generate();
+      await airdrop(owner.publicKey);
+      await airdrop(operator.publicKey);
+      profilePDA = await registerAgent(owner, "HotKeyAgent");
+      await setAgentKeys(owner, profilePDA, cold.publicKey, operator.publicKey);
+    });
 
-      // Client should get back escrow + rent (minus tx fees)
-      // balAfterCancel should be close to balBefore (just tx fees lost)
-      const diff = balBefore - balAfterCancel;
-      // Should only be ~2 tx fees (~10000 lamports)
-      expect(diff).to.be.lessThan(100_000);
+    it("stores the keys, defaulting payout to the owner", async () => {
+      const fresh = Keypair.generate();
+      await airdrop(fresh.publicKey);
+      const freshProfile = await program.account.agentProfile.fetch(
+        await registerAgent(fresh, "FreshAgent")
+      );
+      expect(freshProfile.payout.toBase58()).to.equal(fresh.publicKey.toBase58());
+      expect(freshProfile.operator).to.be.null;
+
+      const profile = await program.account.agentProfile.fetch(profilePDA);
+      expect(profile.payout.toBase58()).to.equal(cold.publicKey.toBase58());
+      expect(profile.operator.toBase58()).to.equal(operator.publicKey.toBase58());
     });
 
-    it("rejects if status != Pending (InProgress)", async () => {
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
-      // Agent starts working (delegates to make it InProgress via delegate, or via update)
-      await delegateTask(
-        agentOwnerA,
+    it("operator delivers and the payment lands in the payout wallet", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, profilePDA);
+      await updateJob(operator, jobPDA);
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.status).to.deep.equal({ completed: {} });
+
+      try {
+        await releasePayment(clientKp, owner.publicKey, profilePDA, jobPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "Unauthorized");
+      }
+      await releasePayment(clientKp, cold.publicKey, profilePDA, jobPDA);
+      expect(await connection.getBalance(cold.publicKey)).to.equal(PAYMENT.toNumber());
+    });
+
+    it("operator delegates and disputes on the owner's behalf", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, profilePDA);
+      const { childJobPDA } = await delegateTask(
+        operator,
         jobPDA,
         agentProfileB,
         new BN(LAMPORTS_PER_SOL / 10)
       );
-      // Job is now InProgress (delegate auto-flips)
+      const child = await program.account.job.fetch(childJobPDA);
+      expect(child.client.toBase58()).to.equal(operator.publicKey.toBase58());
+
+      await raiseDispute(operator, jobPDA);
+      const job = await program.account.job.fetch(jobPDA);
+      expect(job.status).to.deep.equal({ disputed: {} });
+    });
+
+    it("a child the operator cancels refunds the parent, not the hot key", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, profilePDA);
+      const amount = new BN(LAMPORTS_PER_SOL / 4);
+      const { childJobPDA } = await delegateTask(operator, jobPDA, agentProfileB, amount);
+
+      const before = await connection.getBalance(operator.publicKey);
+      await cancelJob(operator, childJobPDA);
+      const after = await connection.getBalance(operator.publicKey);
+      // The operator only recovers the child's rent it paid
+      expect(after - before).to.be.lessThan(amount.toNumber());
+
+      const parent = await program.account.job.fetch(jobPDA);
+      expect(parent.escrowLamports.toNumber()).to.equal(PAYMENT.toNumber());
+      expect(parent.delegatedTotal.toNumber()).to.equal(0);
+      expect(parent.activeChildren).to.equal(0);
+    });
+
+    it("cancel_job_tree returns each child's rent to the key that delegated it", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, profilePDA);
+      const amount = new BN(LAMPORTS_PER_SOL / 10);
+      const { childJobPDA: byOwner } = await delegateTask(owner, jobPDA, agentProfileB, amount);
+      const { childJobPDA: byOperator } = await delegateTask(operator, jobPDA, agentProfileB, amount);
+      const childRent = await connection.getMinimumBalanceForRentExemption(
+        (await connection.getAccountInfo(byOwner))!.data.length
+      );
+
+      const ownerBefore = await connection.getBalance(owner.publicKey);
+      const operatorBefore = await connection.getBalance(operator.publicKey);
+      await cancelJobTree(clientKp, jobPDA, [byOwner, byOperator]);
+      expect((await connection.getBalance(owner.publicKey)) - ownerBefore).to.equal(childRent);
+      expect((await connection.getBalance(operator.publicKey)) - operatorBefore).to.equal(childRent);
+    });
+
+    it("rejects other keys and a revoked operator", async () => {
+      const { jobPDA } = await invokeAgent(clientKp, profilePDA);
       try {
-        await cancelJob(clientKp, jobPDA);
+        await updateJob(clientKp, jobPDA);
         expect.fail("Should have thrown");
       } catch (err: any) {
-        expectAnchorError(err, "InvalidJobStatus");
+        expectAnchorError(err, "Unauthorized");
+      }
+
+      await setAgentKeys(owner, profilePDA, cold.publicKey, null);
+      try {
+        await updateJob(operator, jobPDA);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "Unauthorized");
       }
+      await setAgentKeys(owner, profilePDA, cold.publicKey, operator.publicKey);
     });
 
-    it("rejects non-client signer", async () => {
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+    it("rejects a default payout wallet or a non-owner caller", async () => {
       try {
-        await cancelJob(agentOwnerA, jobPDA);
+        await setAgentKeys(owner, profilePDA, PublicKey.default, null);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidPayoutWallet");
+      }
+      try {
+        await program.methods
+          .setAgentKeys(operator.publicKey, operator.publicKey)
+          .accountsPartial({ owner: operator.publicKey, agentProfile: profilePDA })
+          .signers([operator])
+          .rpc();
         expect.fail("Should have thrown");
       } catch (err: any) {
         expectAnchorError(err, "Unauthorized");
@@ -594,222 +3280,282 @@ describe("agent-protocol", () => {
   });
 
   // ═══════════════════════════════════════
-  //  delegate_task
+  //  Agent ownership transfer
   // ═══════════════════════════════════════
 
-  describe("delegate_task", () => {
-    it("creates child job linked to parent with correct escrow", async () => {
-      const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
-      const delegateAmt = new BN(LAMPORTS_PER_SOL / 4);
-      const { childJobPDA } = await delegateTask(
-        agentOwnerA,
-        parentPDA,
-        agentProfileB,
-        delegateAmt
-      );
-
-      const child = await program.account.job.fetch(childJobPDA);
-      expect(child.parentJob!.toBase58()).to.equal(parentPDA.toBase58());
-      expect(child.escrowLamports.toNumber()).to.equal(delegateAmt.toNumber());
-      expect(child.agent.toBase58()).to.equal(agentOwnerB.publicKey.toBase58());
-      expect(Object.keys(child.status)[0]).to.equal("pending");
-
-      const parent = await program.account.job.fetch(parentPDA);
-      expect(parent.activeChildren).to.equal(1);
-      expect(parent.escrowLamports.toNumber()).to.equal(
-        PAYMENT.toNumber() - delegateAmt.toNumber()
-      );
-      // Parent auto-flipped to InProgress
-      expect(Object.keys(parent.status)[0]).to.equal("inProgress");
+  describe("agent ownership transfer", () => {
+    let oldOwner: Keypair;
+    let newOwner: Keypair;
+    let profilePDA: PublicKey;
+
+    before(async () => {
+      oldOwner = Keypair.generate();
+      newOwner = Keypair.generate();
+      await airdrop(oldOwner.publicKey);
+      await airdrop(newOwner.publicKey);
+      profilePDA = await registerAgent(oldOwner, "RotatingAgent");
     });
 
-    it("rejects delegation exceeding parent escrow", async () => {
-      const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
+    it("only the proposed key can accept", async () => {
       try {
-        await delegateTask(
-          agentOwnerA,
-          parentPDA,
-          agentProfileB,
-          new BN(PAYMENT.toNumber() + 1) // more than escrowed
-        );
+        await acceptAgentTransfer(newOwner, profilePDA);
         expect.fail("Should have thrown");
       } catch (err: any) {
-        expectAnchorError(err, "InsufficientEscrow");
+        expectAnchorError(err, "NoPendingTransfer");
       }
-    });
-
-    it("rejects non-agent signer", async () => {
-      const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
+      await proposeAgentTransfer(oldOwner, profilePDA, newOwner.publicKey);
       try {
-        await delegateTask(
-          clientKp, // wrong signer
-          parentPDA,
-          agentProfileB,
-          new BN(LAMPORTS_PER_SOL / 10)
-        );
+        await acceptAgentTransfer(clientKp, profilePDA);
         expect.fail("Should have thrown");
       } catch (err: any) {
         expectAnchorError(err, "Unauthorized");
       }
+      await proposeAgentTransfer(oldOwner, profilePDA, null);
+      expect((await program.account.agentProfile.fetch(profilePDA)).pendingOwner).to.be.null;
     });
 
-    it("rejects empty description", async () => {
-      const { jobPDA: parentPDA } = await invokeAgent(clientKp, agentProfileA);
+    it("keeps reputation across the key rotation once no jobs are in flight", async () => {
+      const { jobPDA: done } = await invokeAgent(clientKp, profilePDA);
+      await proposeAgentTransfer(oldOwner, profilePDA, newOwner.publicKey);
       try {
-        const ts = nextTs();
-        const [childPDA] = getJobPDA(
-          agentOwnerA.publicKey,
-          agentProfileB,
-          ts
-        );
-        await program.methods
-          .delegateTask("", new BN(LAMPORTS_PER_SOL / 10), ts)
-          .accountsPartial({
-            delegatingAgent: agentOwnerA.publicKey,
-            parentJob: parentPDA,
-            subAgentProfile: agentProfileB,
-          })
-          .signers([agentOwnerA])
-          .rpc();
+        await acceptAgentTransfer(newOwner, profilePDA);
         expect.fail("Should have thrown");
       } catch (err: any) {
-        expectAnchorError(err, "EmptyDescription");
+        expectAnchorError(err, "AgentHasActiveJobs");
+      }
+
+      await updateJob(oldOwner, done);
+      await releasePayment(clientKp, oldOwner.publicKey, profilePDA, done);
+      await acceptAgentTransfer(newOwner, profilePDA);
+      let profile = await program.account.agentProfile.fetch(profilePDA);
+      expect(profile.owner.toBase58()).to.equal(newOwner.publicKey.toBase58());
+      expect(profile.agentId.toBase58()).to.equal(oldOwner.publicKey.toBase58());
+      expect(profile.payout.toBase58()).to.equal(newOwner.publicKey.toBase58());
+      expect(profile.jobsCompleted).to.equal(1);
+
+      // The old key no longer controls the agent
+      const { jobPDA: open } = await invokeAgent(clientKp, profilePDA);
+      try {
+        await updateJob(oldOwner, open);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "Unauthorized");
       }
+
+      await updateJob(newOwner, open);
+      const balBefore = await connection.getBalance(newOwner.publicKey);
+      await releasePayment(clientKp, newOwner.publicKey, profilePDA, open);
+      expect(await connection.getBalance(newOwner.publicKey)).to.equal(balBefore + PAYMENT.toNumber());
+      profile = await program.account.agentProfile.fetch(profilePDA);
+      expect(profile.jobsCompleted).to.equal(2);
+      expect(profile.activeJobs).to.equal(0);
     });
   });
 
   // ═══════════════════════════════════════
-  //  raise_dispute
+  //  Agent metadata
   // ═══════════════════════════════════════
 
-  describe("raise_dispute", () => {
-    it("client can raise dispute", async () => {
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
-      await raiseDispute(clientKp, jobPDA);
-      const job = await program.account.job.fetch(jobPDA);
-      expect(Object.keys(job.status)[0]).to.equal("disputed");
-      expect(job.disputedAt).to.not.be.null;
+  describe("agent metadata", () => {
+    const FORMAT_TEXT = 1 << 0;
+    const FORMAT_JSON = 1 << 1;
+
+    it("publishes and updates the endpoint and metadata", async () => {
+      const metadataPDA = await setAgentMetadata(
+        agentOwnerA,
+        agentProfileA,
+        "https://agent-a.example.com/v1/jobs",
+        "ipfs://QmAgentAMetadata",
+        FORMAT_TEXT | FORMAT_JSON
+      );
+      let metadata = await program.account.agentMetadata.fetch(metadataPDA);
+      expect(metadata.agentProfile.toBase58()).to.equal(agentProfileA.toBase58());
+      expect(metadata.endpointUri).to.equal("https://agent-a.example.com/v1/jobs");
+      expect(metadata.metadataHash).to.deep.equal(sha256("ipfs://QmAgentAMetadata"));
+      expect(metadata.inputFormats).to.equal(FORMAT_TEXT | FORMAT_JSON);
+      expect(metadata.schemaVersion).to.equal(1);
+
+      await setAgentMetadata(agentOwnerA, agentProfileA, "https://agent-a.example.com/v2/jobs");
+      metadata = await program.account.agentMetadata.fetch(metadataPDA);
+      expect(metadata.endpointUri).to.equal("https://agent-a.example.com/v2/jobs");
+      expect(metadata.metadataUri).to.equal("");
     });
 
-    it("agent can raise dispute", async () => {
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
-      await raiseDispute(agentOwnerA, jobPDA);
-      const job = await program.account.job.fetch(jobPDA);
-      expect(Object.keys(job.status)[0]).to.equal("disputed");
+    it("rejects a missing endpoint, an unhashed metadata URI or an unknown schema", async () => {
+      for (const [endpoint, schema] of [["", 1], ["https://agent-b.example.com", 2]] as const) {
+        try {
+          await setAgentMetadata(agentOwnerB, agentProfileB, endpoint, "", FORMAT_TEXT, schema);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expectAnchorError(err, "InvalidAgentMetadata");
+        }
+      }
+      try {
+        await program.methods
+          .setAgentMetadata("https://agent-b.example.com", "ipfs://QmNoHash", Array(32).fill(0), FORMAT_TEXT, 1)
+          .accountsPartial({ owner: agentOwnerB.publicKey, agentProfile: agentProfileB })
+          .signers([agentOwnerB])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "InvalidAgentMetadata");
+      }
     });
 
-    it("rejects if status is Cancelled", async () => {
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
-      await cancelJob(clientKp, jobPDA);
-      // Account is closed after cancel, so dispute should fail
+    it("rejects a non-owner", async () => {
       try {
-        await raiseDispute(clientKp, jobPDA);
+        await setAgentMetadata(clientKp, agentProfileA, "https://attacker.example.com");
         expect.fail("Should have thrown");
       } catch (err: any) {
-        expect(err).to.exist;
+        expectAnchorError(err, "Unauthorized");
       }
     });
   });
 
   // ═══════════════════════════════════════
-  //  resolve_dispute_by_timeout
+  //  Agent liveness
   // ═══════════════════════════════════════
 
-  describe("resolve_dispute_by_timeout", () => {
-    it("rejects if status != Disputed", async () => {
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
+  describe("agent liveness", () => {
+    let owner: Keypair;
+    let profilePDA: PublicKey;
+
+    before(async () => {
+      owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      profilePDA = await registerAgent(owner, "HeartbeatAgent");
+    });
+
+    it("rejects an agent whose heartbeat is too old", async () => {
+      const { lastSeenAt } = await program.account.agentProfile.fetch(profilePDA);
+      expect(lastSeenAt).to.not.be.null;
+      await sleep(2000);
       try {
-        await program.methods
-          .resolveDisputeByTimeout()
-          .accountsPartial({ client: clientKp.publicKey, job: jobPDA })
-          .rpc();
+        await invokeAgent(clientKp, profilePDA, PAYMENT, null, "Stale", { maxHeartbeatAge: new BN(1) });
         expect.fail("Should have thrown");
       } catch (err: any) {
-        expectAnchorError(err, "InvalidJobStatus");
+        expectAnchorError(err, "AgentStale");
       }
+      // No liveness requirement, no check
+      await invokeAgent(clientKp, profilePDA, PAYMENT, null, "Any age");
     });
 
-    it("rejects if timeout not reached", async () => {
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
-      await raiseDispute(clientKp, jobPDA);
+    it("heartbeat refreshes last_seen_at", async () => {
+      const before = (await program.account.agentProfile.fetch(profilePDA)).lastSeenAt;
+      await heartbeat(owner, profilePDA);
+      const after = (await program.account.agentProfile.fetch(profilePDA)).lastSeenAt;
+      expect(after.gt(before)).to.be.true;
+      await invokeAgent(clientKp, profilePDA, PAYMENT, null, "Live", { maxHeartbeatAge: new BN(60) });
+    });
+
+    it("rejects a heartbeat from another key", async () => {
       try {
-        await program.methods
-          .resolveDisputeByTimeout()
-          .accountsPartial({ client: clientKp.publicKey, job: jobPDA })
-          .rpc();
+        await heartbeat(clientKp, profilePDA);
         expect.fail("Should have thrown");
       } catch (err: any) {
-        expectAnchorError(err, "DisputeTimeoutNotReached");
+        expectAnchorError(err, "Unauthorized");
       }
     });
   });
 
   // ═══════════════════════════════════════
-  //  rate_agent
+  //  Client access lists
   // ═══════════════════════════════════════
 
-  describe("rate_agent", () => {
-    it("creates rating and updates agent profile sums", async () => {
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
-      await updateJob(agentOwnerA, jobPDA);
-      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
+  describe("client access lists", () => {
+    let owner: Keypair;
+    let outsider: Keypair;
+    let profilePDA: PublicKey;
 
-      const profileBefore = await program.account.agentProfile.fetch(agentProfileA);
-      await rateAgent(clientKp, jobPDA, agentProfileA, 5);
+    before(async () => {
+      owner = Keypair.generate();
+      outsider = Keypair.generate();
+      await airdrop(owner.publicKey);
+      await airdrop(outsider.publicKey);
+      profilePDA = await registerAgent(owner, "PrivateAgent");
+      await setAccessList(owner, profilePDA, { allowlist: {} }, [clientKp.publicKey]);
+    });
 
-      const profileAfter = await program.account.agentProfile.fetch(agentProfileA);
-      expect(profileAfter.ratingCount).to.equal(profileBefore.ratingCount + 1);
-      expect(profileAfter.ratingSum.toNumber()).to.equal(
-        profileBefore.ratingSum.toNumber() + 5
-      );
+    it("allowlist mode only serves listed clients", async () => {
+      await invokeAgent(clientKp, profilePDA, PAYMENT, null, "Org task");
+      try {
+        await invokeAgent(outsider, profilePDA, PAYMENT, null, "Outside task");
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "ClientNotPermitted");
+      }
     });
 
-    it("rejects score outside 1-5 (score 0)", async () => {
+    it("delegate_task checks the sub-agent's list against the delegating key", async () => {
       const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
-      await updateJob(agentOwnerA, jobPDA);
-      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
       try {
-        await rateAgent(clientKp, jobPDA, agentProfileA, 0);
+        await delegateTask(agentOwnerA, jobPDA, profilePDA, new BN(LAMPORTS_PER_SOL / 10));
         expect.fail("Should have thrown");
       } catch (err: any) {
-        expectAnchorError(err, "InvalidRating");
+        expectAnchorError(err, "ClientNotPermitted");
       }
     });
 
-    it("rejects score outside 1-5 (score 6)", async () => {
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
-      await updateJob(agentOwnerA, jobPDA);
-      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
+    it("team and batch invocations check each agent's list", async () => {
+      await invokeAgentsBatch(clientKp, [{ profile: profilePDA, title: "Batched", payment: PAYMENT }]);
+      await invokeTeam(clientKp, profilePDA, [agentProfileB], [5_000, 5_000]);
+      await invokeTeam(clientKp, agentProfileA, [profilePDA], [5_000, 5_000]);
+
       try {
-        await rateAgent(clientKp, jobPDA, agentProfileA, 6);
+        await invokeAgentsBatch(outsider, [{ profile: profilePDA, title: "Batched", payment: PAYMENT }]);
         expect.fail("Should have thrown");
       } catch (err: any) {
-        expectAnchorError(err, "InvalidRating");
+        expectAnchorError(err, "ClientNotPermitted");
+      }
+      try {
+        await invokeTeam(outsider, agentProfileA, [profilePDA], [5_000, 5_000]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "ClientNotPermitted");
       }
     });
 
-    it("rejects duplicate rating (same job)", async () => {
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
-      await updateJob(agentOwnerA, jobPDA);
-      await releasePayment(clientKp, agentOwnerA.publicKey, agentProfileA, jobPDA);
-      await rateAgent(clientKp, jobPDA, agentProfileA, 4);
+    it("rejects an access list that belongs to another agent", async () => {
       try {
-        await rateAgent(clientKp, jobPDA, agentProfileA, 3);
+        await program.methods
+          .invokeAgentsBatch([{ title: "Swapped list", paymentLamports: PAYMENT, spec: null }], null)
+          .accountsPartial({ client: outsider.publicKey })
+          .remainingAccounts([
+            { pubkey: profilePDA, isWritable: true, isSigner: false },
+            { pubkey: getAccessListPDA(agentProfileA)[0], isWritable: false, isSigner: false },
+            { pubkey: await nextJobPDA(outsider.publicKey), isWritable: true, isSigner: false },
+          ])
+          .signers([outsider])
+          .rpc();
         expect.fail("Should have thrown");
       } catch (err: any) {
-        // PDA already init'd
-        expect(err).to.exist;
+        expectAnchorError(err, "AccessListMismatch");
       }
     });
 
-    it("rejects rating before payment (status != Finalized)", async () => {
-      const { jobPDA } = await invokeAgent(clientKp, agentProfileA);
-      await updateJob(agentOwnerA, jobPDA); // Completed, not Finalized
+    it("blocklist mode turns away listed clients, open mode lifts it", async () => {
+      await setAccessList(owner, profilePDA, { blocklist: {} }, [outsider.publicKey]);
+      await invokeAgent(clientKp, profilePDA, PAYMENT, null, "Still welcome");
       try {
-        await rateAgent(clientKp, jobPDA, agentProfileA, 5);
+        await invokeAgent(outsider, profilePDA, PAYMENT, null, "Blocked");
         expect.fail("Should have thrown");
       } catch (err: any) {
-        expectAnchorError(err, "InvalidJobStatus");
+        expectAnchorError(err, "ClientNotPermitted");
+      }
+
+      await setAccessList(owner, profilePDA, { open: {} }, []);
+      await invokeAgent(outsider, profilePDA, PAYMENT, null, "Open again");
+      const list = await program.account.agentAccessList.fetch(getAccessListPDA(profilePDA)[0]);
+      expect(list.mode).to.deep.equal({ open: {} });
+    });
+
+    it("rejects a list over 16 clients", async () => {
+      const clients = Array.from({ length: 17 }, () => Keypair.generate().publicKey);
+      try {
+        await setAccessList(owner, profilePDA, { allowlist: {} }, clients);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expectAnchorError(err, "AccessListTooLong");
       }
     });
   });
@@ -1416,11 +4162,10 @@ describe("agent-protocol", () => {
       };
 
       // 1) invoke_agent — capture JobCreated event
-      const ts = nextTs();
-      const [jobPDA] = getJobPDA(client.publicKey, profilePDA, ts);
+      const jobPDA = await nextJobPDA(client.publicKey);
       const invokeTx = await program.methods
-        .invokeAgent("Event test", PAYMENT, null, ts)
-        .accountsPartial({ client: client.publicKey, agentProfile: profilePDA })
+        .invokeAgent("Event test", PAYMENT, invokeOptions())
+        .accountsPartial({ client: client.publicKey, agentProfile: profilePDA, job: jobPDA, jobExtension: null, agentStake: null })
         .signers([client])
         .rpc();
       const invokeEvents = await parseLogs(invokeTx);
@@ -1432,24 +4177,32 @@ describe("agent-protocol", () => {
 
       // 2) update_job — capture JobCompleted event
       const updateTx = await program.methods
-        .updateJob("https://event-test.com")
-        .accountsPartial({ agent: owner.publicKey, job: jobPDA })
+        .updateJob("https://event-test.com", sha256("https://event-test.com"), null)
+        .accountsPartial({
+          agent: owner.publicKey,
+          job: jobPDA,
+          agentProfile: getAgentProfilePDA(owner.publicKey)[0],
+          jobExtension: null,
+        })
         .signers([owner])
         .rpc();
       const updateEvents = await parseLogs(updateTx);
       const jobCompletedEvent = updateEvents.find((e) => e.name === "jobCompleted");
       expect(jobCompletedEvent).to.exist;
       expect(jobCompletedEvent.data.resultUri).to.equal("https://event-test.com");
+      expect(jobCompletedEvent.data.resultHash).to.deep.equal(sha256("https://event-test.com"));
+      expect(jobCompletedEvent.data.childrenCompleted).to.equal(0);
 
       // 3) release_payment — capture PaymentReleased event
       const releaseTx = await program.methods
-        .releasePayment()
+        .releasePayment(null)
         .accountsPartial({
           client: client.publicKey,
           agent: owner.publicKey,
           agentProfile: profilePDA,
           job: jobPDA,
           parentJob: null,
+          team: null,
         })
         .signers([client])
         .rpc();
@@ -1459,7 +4212,9 @@ describe("agent-protocol", () => {
       expect(paymentEvent.data.job.toBase58()).to.equal(jobPDA.toBase58());
       expect(paymentEvent.data.agent.toBase58()).to.equal(owner.publicKey.toBase58());
       expect(paymentEvent.data.amount.toNumber()).to.equal(PAYMENT.toNumber());
+      expect(paymentEvent.data.bonus.toNumber()).to.equal(0);
       expect(paymentEvent.data.autoReleased).to.be.false;
+      expect(paymentEvent.data.marginLamports).to.be.null;
 
       console.log("\n  📡 Event decoding verified:");
       console.log("     JobCreated ✓  JobCompleted ✓  PaymentReleased ✓");
@@ -1502,43 +4257,47 @@ describe("agent-protocol", () => {
       const regDetails = await fetchTx(regTx);
 
       // Invoke
-      const ts1 = nextTs();
-      const [jobPDA1] = getJobPDA(client.publicKey, profilePDA, ts1);
+      const jobPDA1 = await nextJobPDA(client.publicKey);
       const invokeTx = await program.methods
-        .invokeAgent("CU test task", PAYMENT, null, ts1)
-        .accountsPartial({ client: client.publicKey, agentProfile: profilePDA })
+        .invokeAgent("CU test task", PAYMENT, invokeOptions())
+        .accountsPartial({ client: client.publicKey, agentProfile: profilePDA, job: jobPDA1, jobExtension: null, agentStake: null })
         .signers([client])
         .rpc();
       const invokeDetails = await fetchTx(invokeTx);
 
       // Update
       const updateTx = await program.methods
-        .updateJob("https://cu-test.com")
-        .accountsPartial({ agent: owner.publicKey, job: jobPDA1 })
+        .updateJob("https://cu-test.com", sha256("https://cu-test.com"), null)
+        .accountsPartial({
+          agent: owner.publicKey,
+          job: jobPDA1,
+          agentProfile: getAgentProfilePDA(owner.publicKey)[0],
+          jobExtension: null,
+        })
         .signers([owner])
         .rpc();
       const updateDetails = await fetchTx(updateTx);
 
       // Release
       const releaseTx = await program.methods
-        .releasePayment()
+        .releasePayment(null)
         .accountsPartial({
           client: client.publicKey,
           agent: owner.publicKey,
           agentProfile: profilePDA,
           job: jobPDA1,
           parentJob: null,
+          team: null,
         })
         .signers([client])
         .rpc();
       const releaseDetails = await fetchTx(releaseTx);
 
       // Delegate (new job for this)
-      const ts2 = nextTs();
-      const [jobPDA2] = getJobPDA(client.publicKey, profilePDA, ts2);
+      const jobPDA2 = await nextJobPDA(client.publicKey);
       await program.methods
-        .invokeAgent("CU delegate test", PAYMENT, null, ts2)
-        .accountsPartial({ client: client.publicKey, agentProfile: profilePDA })
+        .invokeAgent("CU delegate test", PAYMENT, invokeOptions())
+        .accountsPartial({ client: client.publicKey, agentProfile: profilePDA, job: jobPDA2, jobExtension: null, agentStake: null })
         .signers([client])
         .rpc();
 
@@ -1552,13 +4311,15 @@ describe("agent-protocol", () => {
         .signers([subOwner])
         .rpc();
 
-      const ts3 = nextTs();
       const delegateTx = await program.methods
-        .delegateTask("CU subtask", new BN(LAMPORTS_PER_SOL / 4), ts3)
+        .delegateTask("CU subtask", new BN(LAMPORTS_PER_SOL / 4), null)
         .accountsPartial({
           delegatingAgent: owner.publicKey,
           parentJob: jobPDA2,
+          agentProfile: getAgentProfilePDA(owner.publicKey)[0],
+          parentExtension: null,
           subAgentProfile: subProfile,
+          childJob: await nextJobPDA(owner.publicKey),
         })
         .signers([owner])
         .rpc();
@@ -3,6 +3,7 @@ import dotenv from "dotenv";
 import { ACTIONS_CORS_HEADERS } from "@solana/actions";
 import actionsRouter from "./routes/actions";
 import invokeRouter from "./routes/invoke";
+import specsRouter from "./routes/specs";
 
 dotenv.config();
 
@@ -26,6 +27,7 @@ app.use((req, res, next) => {
 // Mount routes
 app.use(actionsRouter);
 app.use(invokeRouter);
+app.use(specsRouter);
 
 // Root route
 app.get("/", (_req, res) => {
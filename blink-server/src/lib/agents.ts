@@ -4,20 +4,31 @@ import { program, getAgentProfilePDA } from "./program";
 export interface AgentCatalogEntry {
   profilePDA: PublicKey;
   owner: PublicKey;
+  /** Registration key seeding the profile PDA; unchanged by ownership transfers */
+  agentId: PublicKey;
   name: string;
   description: string;
   priceLamports: number;
   rating: string;
   jobsCompleted: number;
   isActive: boolean;
+  /** Service endpoint from the agent's metadata PDA, if published */
+  endpointUri: string | null;
+  metadataUri: string | null;
 }
 
 /**
- * Fetch all AgentProfile accounts from the on-chain program and return
- * a formatted catalog array.
+ * Fetch all AgentProfile accounts from the on-chain program, joined with
+ * their AgentMetadata, and return a formatted catalog array.
  */
 export async function getAgentCatalog(): Promise<AgentCatalogEntry[]> {
-  const allProfiles = await (program.account as any).agentProfile.all();
+  const [allProfiles, allMetadata] = await Promise.all([
+    (program.account as any).agentProfile.all(),
+    (program.account as any).agentMetadata.all(),
+  ]);
+  const metadataByProfile = new Map<string, any>(
+    allMetadata.map((item: any) => [item.account.agentProfile.toBase58(), item.account])
+  );
 
   return allProfiles.map((item: any) => {
     const account = item.account;
@@ -29,15 +40,20 @@ export async function getAgentCatalog(): Promise<AgentCatalogEntry[]> {
         ? (ratingSum / ratingCount).toFixed(1)
         : "New";
 
+    const metadata = metadataByProfile.get(item.publicKey.toBase58());
+
     return {
       profilePDA: item.publicKey,
       owner: account.owner as PublicKey,
+      agentId: account.agentId as PublicKey,
       name: account.name as string,
       description: account.description as string,
       priceLamports: Number(account.priceLamports.toString()),
       rating,
       jobsCompleted: Number(account.jobsCompleted.toString()),
       isActive: account.isActive as boolean,
+      endpointUri: metadata ? (metadata.endpointUri as string) : null,
+      metadataUri: metadata?.metadataUri ? (metadata.metadataUri as string) : null,
     };
   });
 }
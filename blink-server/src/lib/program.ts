@@ -22,8 +22,9 @@ const provider = new anchor.AnchorProvider(
 const program = new anchor.Program(idl as any, provider);
 
 /**
- * Derive AgentProfile PDA from the agent owner's public key.
- * Seeds: ["agent", owner]
+ * Derive AgentProfile PDA from the agent's ID (the key it registered with,
+ * which stays the same when ownership is transferred).
+ * Seeds: ["agent", agent_id]
  */
 export const getAgentProfilePDA = (
   owner: PublicKey
@@ -34,22 +35,55 @@ export const getAgentProfilePDA = (
   );
 
 /**
- * Derive Job PDA from client, agentProfile, and timestamp seed.
- * Seeds: ["job", client, agentProfile, timestamp_le_bytes]
+ * Derive the JobCounter PDA tracking a creator's job nonces.
+ * Seeds: ["job_counter", creator]
+ */
+export const getJobCounterPDA = (
+  creator: PublicKey
+): [PublicKey, number] =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("job_counter"), creator.toBuffer()],
+    PROGRAM_ID
+  );
+
+/**
+ * Derive Job PDA from its creator and nonce.
+ * Seeds: ["job", creator, nonce_le_bytes]
  */
 export const getJobPDA = (
-  client: PublicKey,
-  agentProfile: PublicKey,
-  ts: anchor.BN
+  creator: PublicKey,
+  nonce: anchor.BN
 ): [PublicKey, number] =>
   PublicKey.findProgramAddressSync(
     [
       Buffer.from("job"),
-      client.toBuffer(),
-      agentProfile.toBuffer(),
-      ts.toArrayLike(Buffer, "le", 8),
+      creator.toBuffer(),
+      nonce.toArrayLike(Buffer, "le", 8),
     ],
     PROGRAM_ID
   );
 
+/**
+ * Nonce the creator's next job will use (0 before their first job).
+ */
+export const getNextJobNonce = async (
+  creator: PublicKey
+): Promise<anchor.BN> => {
+  const counter: any = await (program.account as any).jobCounter.fetchNullable(
+    getJobCounterPDA(creator)[0]
+  );
+  return counter ? counter.nextNonce : new anchor.BN(0);
+};
+
+/**
+ * Every job PDA the creator could have opened, oldest first. Nonces spent on
+ * job requests or subscriptions yield addresses with no account behind them.
+ */
+export const listJobPDAs = async (creator: PublicKey): Promise<PublicKey[]> => {
+  const count = (await getNextJobNonce(creator)).toNumber();
+  return Array.from({ length: count }, (_, i) =>
+    getJobPDA(creator, new anchor.BN(i))[0]
+  );
+};
+
 export { connection, program, PROGRAM_ID };
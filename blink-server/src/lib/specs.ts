@@ -0,0 +1,96 @@
+import { createHash } from "crypto";
+import { mkdir, readdir, readFile, stat, writeFile } from "fs/promises";
+import path from "path";
+
+/** Matches `#[max_len(64)]` on Job.title */
+export const MAX_TITLE_BYTES = 64;
+
+/** Longest brief the server will publish */
+export const MAX_SPEC_BYTES = 16 * 1024;
+
+/** Where published briefs are stored, one file per SHA-256 */
+const SPEC_DIR = process.env.SPEC_DIR || path.join(process.cwd(), "specs");
+
+/** Caps on the whole store, so unauthenticated callers cannot fill the disk */
+const MAX_SPEC_FILES = Number(process.env.MAX_SPEC_FILES || 10_000);
+const MAX_SPEC_STORE_BYTES = Number(process.env.MAX_SPEC_STORE_BYTES || 64 * 1024 * 1024);
+
+const HEX_HASH = /^[0-9a-f]{64}$/;
+
+/** Files and bytes in the store, counted from disk on first use */
+let usage: Promise<{ files: number; bytes: number }> | null = null;
+
+const loadUsage = () => {
+  usage ??= (async () => {
+    await mkdir(SPEC_DIR, { recursive: true });
+    const names = (await readdir(SPEC_DIR)).filter((name) => HEX_HASH.test(name));
+    let bytes = 0;
+    for (const name of names) {
+      bytes += (await stat(path.join(SPEC_DIR, name))).size;
+    }
+    return { files: names.length, bytes };
+  })();
+  return usage;
+};
+
+/**
+ * Shorten a task to fit the on-chain title, cutting on a character boundary
+ * and marking the cut with an ellipsis.
+ */
+export const toTitle = (task: string): string => {
+  if (Buffer.byteLength(task, "utf8") <= MAX_TITLE_BYTES) return task;
+  const budget = MAX_TITLE_BYTES - Buffer.byteLength("…", "utf8");
+  let title = "";
+  for (const ch of task) {
+    if (Buffer.byteLength(title + ch, "utf8") > budget) break;
+    title += ch;
+  }
+  return `${title}…`;
+};
+
+/**
+ * Store a task brief and return the JobSpec pinning it, or null if the store
+ * is full. The document is content-addressed, so the agent can check whatever
+ * it fetches against the hash escrowed on-chain.
+ */
+export const publishSpec = async (
+  text: string,
+  baseUrl: string
+): Promise<{ uri: string; hash: number[] } | null> => {
+  const size = Buffer.byteLength(text, "utf8");
+  if (size > MAX_SPEC_BYTES) return null;
+
+  const digest = createHash("sha256").update(text, "utf8").digest();
+  const hex = digest.toString("hex");
+  const spec = { uri: `${baseUrl}/api/specs/${hex}`, hash: Array.from(digest) };
+
+  const current = await loadUsage();
+  if (current.files >= MAX_SPEC_FILES || current.bytes + size > MAX_SPEC_STORE_BYTES) {
+    return null;
+  }
+  // Reserve the space before writing so concurrent requests see it
+  current.files += 1;
+  current.bytes += size;
+  try {
+    await writeFile(path.join(SPEC_DIR, hex), text, { encoding: "utf8", flag: "wx" });
+  } catch (err: any) {
+    current.files -= 1;
+    current.bytes -= size;
+    // Same brief published before: the stored copy is identical
+    if (err.code === "EEXIST") return spec;
+    throw err;
+  }
+  return spec;
+};
+
+/**
+ * A published brief by its hex SHA-256, or null if unknown.
+ */
+export const getSpec = async (hex: string): Promise<string | null> => {
+  if (!HEX_HASH.test(hex)) return null;
+  try {
+    return await readFile(path.join(SPEC_DIR, hex), "utf8");
+  } catch {
+    return null;
+  }
+};
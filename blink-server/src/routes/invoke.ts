@@ -1,8 +1,15 @@
 import { Router, Request, Response } from "express";
 import { PublicKey, Transaction } from "@solana/web3.js";
 import BN from "bn.js";
-import { connection, program, getAgentProfilePDA, getJobPDA } from "../lib/program";
+import {
+  connection,
+  program,
+  getAgentProfilePDA,
+  getJobPDA,
+  getNextJobNonce,
+} from "../lib/program";
 import { getAgentCatalog } from "../lib/agents";
+import { MAX_SPEC_BYTES, MAX_TITLE_BYTES, publishSpec, toTitle } from "../lib/specs";
 
 const router = Router();
 
@@ -36,14 +43,14 @@ router.get("/api/actions/invoke", async (_req: Request, res: Response) => {
       return {
         type: "transaction" as const,
         label,
-        href: `/api/actions/invoke?agent=${agent.owner.toBase58()}`,
+        href: `/api/actions/invoke?agent=${agent.agentId.toBase58()}`,
       };
     });
 
     // Determine a default agent for the custom task input
     const defaultAgentOwner =
       uniqueAgents.length > 0
-        ? uniqueAgents[0].owner.toBase58()
+        ? uniqueAgents[0].agentId.toBase58()
         : "";
 
     const actions: any[] = [...agentButtons];
@@ -119,6 +126,12 @@ router.post("/api/actions/invoke", async (req: Request, res: Response) => {
     }
 
     const task = (req.query.task as string) || "General task";
+    const taskBytes = Buffer.byteLength(task, "utf8");
+    if (taskBytes > MAX_SPEC_BYTES) {
+      return res.status(400).json({
+        error: `Task too long (max ${MAX_SPEC_BYTES} bytes)`,
+      });
+    }
 
     // Derive the AgentProfile PDA
     const [agentProfilePDA] = getAgentProfilePDA(agentOwner);
@@ -137,19 +150,44 @@ router.post("/api/actions/invoke", async (req: Request, res: Response) => {
       return res.status(400).json({ error: "Agent is not currently active" });
     }
 
+    // Job titles are capped on-chain; longer briefs are published as an
+    // off-chain spec and the title keeps a shortened copy. Publishing waits
+    // until the request is known to be valid.
+    const title = toTitle(task);
+    let spec: { uri: string; hash: number[] } | null = null;
+    if (taskBytes > MAX_TITLE_BYTES) {
+      spec = await publishSpec(task, `${req.protocol}://${req.get("host")}`);
+      if (spec === null) {
+        return res.status(503).json({
+          error: `Spec storage is full; keep the task under ${MAX_TITLE_BYTES} bytes`,
+        });
+      }
+    }
+
     // Build the invoke_agent instruction
-    const ts = new BN(Math.floor(Date.now() / 1000));
     const payment = new BN(profile.priceLamports.toString());
     const autoRelease = new BN(3600); // 1 hour default
 
-    const [jobPDA] = getJobPDA(clientPubkey, agentProfilePDA, ts);
+    const [jobPDA] = getJobPDA(clientPubkey, await getNextJobNonce(clientPubkey));
 
     const ix = await program.methods
-      .invokeAgent(task, payment, autoRelease, ts)
+      .invokeAgent(title, payment, {
+        autoReleaseSeconds: autoRelease,
+        stakeRequirement: null,
+        stream: null,
+        clientEncryptionKey: null,
+        spec,
+        delegationPolicy: null,
+        requiredCapabilities: 0,
+        priceTier: 0,
+        maxHeartbeatAge: null,
+      })
       .accountsPartial({
         client: clientPubkey,
         agentProfile: agentProfilePDA,
         job: jobPDA,
+        jobExtension: null,
+        agentStake: null,
       })
       .instruction();
 
@@ -171,7 +209,7 @@ router.post("/api/actions/invoke", async (req: Request, res: Response) => {
     return res.json({
       type: "transaction",
       transaction: serializedTx,
-      message: `Hiring ${profile.name} for "${task}" -- ${priceSOL} SOL escrowed`,
+      message: `Hiring ${profile.name} for "${title}" -- ${priceSOL} SOL escrowed`,
     });
   } catch (err: any) {
     console.error("POST /api/actions/invoke error:", err);
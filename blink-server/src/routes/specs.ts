@@ -0,0 +1,20 @@
+import { Router, Request, Response } from "express";
+import { getSpec } from "../lib/specs";
+
+const router = Router();
+
+/**
+ * GET /api/specs/:hash
+ *
+ * Serves a task brief published by the invoke route, addressed by the
+ * SHA-256 pinned in the job's spec.
+ */
+router.get("/api/specs/:hash", async (req: Request, res: Response) => {
+  const spec = await getSpec(req.params.hash);
+  if (spec === null) {
+    return res.status(404).json({ error: "Spec not found" });
+  }
+  return res.type("text/plain").send(spec);
+});
+
+export default router;